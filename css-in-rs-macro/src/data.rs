@@ -1,11 +1,38 @@
-use syn::parse::{Parse, ParseStream};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
 
+use syn::{
+    parse::{Parse, ParseStream},
+    Token,
+};
+
+use rules::{
+    entry::Composed,
+    header::{field_name, Part},
+};
+
+pub mod css;
 pub mod rules;
+#[cfg(feature = "scss")]
+mod scss;
 pub mod signature;
 
+mod kw {
+    syn::custom_keyword!(css);
+    syn::custom_keyword!(scss);
+}
+
 pub struct Style {
     pub signature: signature::Signature,
     pub rules: rules::RuleList,
+    /// Files the style was read from. The generated code depends on them,
+    /// so changes trigger a recompilation
+    pub files: Vec<String>,
+    /// `let` statements in front of the rules, evaluated once per call of
+    /// `generate`
+    pub prologue: Vec<syn::Local>,
 }
 
 impl Style {
@@ -16,38 +43,349 @@ impl Style {
         let mut result = Vec::new();
         result.reserve_exact(classnames.len());
         for (classname, span) in classnames {
-            let ident = syn::Ident::new(&classname, span);
+            let ident = syn::Ident::new(&field_name(&classname), span);
             result.push(ident);
         }
 
         result
     }
+
+    /// Classnames are mapped to snake_case members of the generated struct,
+    /// i.e. `btn-primary` becomes `btn_primary`. Reports classnames which
+    /// would be mapped to the same member, like `a-b` and `a_b`.
+    fn check_classnames(&self) -> syn::Result<()> {
+        let mut classnames = Default::default();
+        self.rules.collect_classnames(&mut classnames);
+
+        let mut fields = BTreeMap::<String, &str>::new();
+        for (classname, span) in &classnames {
+            let field = field_name(classname);
+            if let Some(other) = fields.insert(field.clone(), classname) {
+                let msg = format!(
+                    "The classnames `{other}` and `{classname}` collide; both are mapped to the member `{field}`"
+                );
+                return Err(syn::Error::new(*span, msg));
+            }
+        }
+
+        // Classnames and names of keyframes are scoped alike, so they
+        // would share the scoped name and the member
+        let mut error = None;
+        let keyframes = self.get_keyframes();
+        self.rules.for_each_header(&mut |header| {
+            for part in &header.parts {
+                if let Part::ClassName(name) = part {
+                    if error.is_none() && keyframes.contains(name) {
+                        let msg = format!(
+                            "The classname `{name}` collides with `@keyframes {name}`; both are \
+                            mapped to the member `{}`",
+                            field_name(name)
+                        );
+                        error = Some(syn::Error::new(header.span, msg));
+                    }
+                }
+            }
+        });
+
+        match error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Reports classes composed via `composes: ...` which are not part of
+    /// this style. Use `composes: name from global` for other classes.
+    fn check_compositions(&self) -> syn::Result<()> {
+        let mut compositions = BTreeMap::new();
+        self.rules.collect_compositions(&mut compositions)?;
+
+        let mut classnames = Default::default();
+        self.rules.collect_classnames(&mut classnames);
+
+        for composed in compositions.values().flatten() {
+            if !composed.global && !classnames.contains_key(&composed.name) {
+                let msg = format!(
+                    "Unknown class `{}` in `composes`; use `composes: {} from global` for classes \
+                    which are not part of this style",
+                    composed.name, composed.name
+                );
+                return Err(syn::Error::new(composed.span, msg));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The classes composed into each class via `composes: ...`, by member
+    /// name. Composed classes which compose other classes in turn include
+    /// them as well.
+    pub fn get_compositions(&self) -> BTreeMap<String, Vec<Composed>> {
+        let mut direct = BTreeMap::new();
+        self.rules
+            .collect_compositions(&mut direct)
+            .expect("checked while parsing");
+
+        fn resolve(
+            direct: &BTreeMap<String, Vec<Composed>>,
+            classname: &str,
+            visited: &mut BTreeSet<String>,
+            result: &mut Vec<Composed>,
+        ) {
+            for composed in direct.get(classname).into_iter().flatten() {
+                if composed.global {
+                    if !result.iter().any(|c| c.global && c.name == composed.name) {
+                        result.push(composed.clone());
+                    }
+                } else if visited.insert(composed.name.clone()) {
+                    result.push(composed.clone());
+                    resolve(direct, &composed.name, visited, result);
+                }
+            }
+        }
+
+        let mut result = BTreeMap::new();
+        for classname in direct.keys() {
+            let mut composed = Vec::new();
+            let mut visited = BTreeSet::from([classname.clone()]);
+            resolve(&direct, classname, &mut visited, &mut composed);
+            result.insert(field_name(classname), composed);
+        }
+        result
+    }
+
+    /// Names of all `@keyframes` rules. Like classnames, they are scoped
+    pub fn get_keyframes(&self) -> BTreeSet<String> {
+        let mut keyframes = Default::default();
+        self.rules.collect_keyframes(&mut keyframes);
+        keyframes
+    }
 }
 
 impl Parse for Style {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let signature = input.parse::<signature::Signature>()?;
 
+        // `(theme: MyTheme) -> MyClasses css { ... }` contains plain css
+        let plain_css = input.parse::<Option<kw::css>>()?.is_some();
+        let scss = input.parse::<Option<kw::scss>>()?;
+
         let content;
         syn::braced!(content in input);
-        let rules = content.parse::<rules::RuleList>()?;
+        let mut bindings = BTreeSet::new();
+        let prologue = parse_prologue(&content, &mut bindings)?;
 
-        let style = Style { signature, rules };
+        let (rules, files) = if let Some(scss) = scss {
+            parse_scss(&content, scss.span)?
+        } else if plain_css {
+            (css::parse(&content)?, Vec::new())
+        } else {
+            (content.parse::<rules::RuleList>()?, Vec::new())
+        };
+
+        check_words(&rules, &bindings)?;
+        let mut style = Style::new(signature, rules, files)?;
+        style.prologue = prologue;
         Ok(style)
     }
 }
 
+/// Names of variables used by the generated code, which must not be shadowed
+const RESERVED: &[&str] = &["css", "counter", "start"];
+
+/// Parses `let` statements in front of the rules, e.g. `let shadow = &theme.shadows[3];`.
+/// Their bindings can be used in all values of the style; their names are
+/// added to `bindings`.
+fn parse_prologue(
+    input: ParseStream,
+    bindings: &mut BTreeSet<String>,
+) -> syn::Result<Vec<syn::Local>> {
+    let mut prologue = Vec::new();
+    while input.peek(Token![let]) {
+        let local = match input.parse::<syn::Stmt>()? {
+            syn::Stmt::Local(local) => local,
+            stmt => return Err(syn::Error::new_spanned(stmt, "Expected a `let` statement")),
+        };
+        check_bindings(&local.pat, bindings)?;
+        prologue.push(local);
+    }
+    Ok(prologue)
+}
+
+/// Checks the identifiers bound by a pattern. Paths and types within the
+/// pattern (e.g. `let x: css::Foo`) are not bindings. The names of the
+/// bindings are added to `bindings`.
+fn check_bindings(pat: &syn::Pat, bindings: &mut BTreeSet<String>) -> syn::Result<()> {
+    let mut check = |pat: &syn::Pat| check_bindings(pat, bindings);
+    match pat {
+        syn::Pat::Ident(pat) => {
+            let ident = &pat.ident;
+            if RESERVED.iter().any(|name| ident == name) {
+                let msg = format!("`{ident}` is used by the generated code; use another name");
+                return Err(syn::Error::new(ident.span(), msg));
+            }
+            bindings.insert(ident.to_string());
+            match &pat.subpat {
+                Some((_, pat)) => check_bindings(pat, bindings),
+                None => Ok(()),
+            }
+        }
+        syn::Pat::Or(pat) => pat.cases.iter().try_for_each(check),
+        syn::Pat::Paren(pat) => check(&pat.pat),
+        syn::Pat::Reference(pat) => check(&pat.pat),
+        syn::Pat::Slice(pat) => pat.elems.iter().try_for_each(check),
+        syn::Pat::Struct(pat) => pat.fields.iter().try_for_each(|field| check(&field.pat)),
+        syn::Pat::Tuple(pat) => pat.elems.iter().try_for_each(check),
+        syn::Pat::TupleStruct(pat) => pat.elems.iter().try_for_each(check),
+        syn::Pat::Type(pat) => check(&pat.pat),
+        _ => Ok(()),
+    }
+}
+
+/// Values like `space-between` or `1 / 3` are css, but so would be
+/// `gap - border` or `total / n`. These are ambiguous if one of their
+/// identifiers is a binding of the prologue.
+fn check_words(rules: &rules::RuleList, bindings: &BTreeSet<String>) -> syn::Result<()> {
+    let mut result = Ok(());
+    rules.for_each_entry(&mut |entry| {
+        let word = entry
+            .words
+            .iter()
+            .find(|word| bindings.contains(&word.to_string()));
+        if let (Some(word), Ok(())) = (word, &result) {
+            let msg = format!(
+                "Ambiguous value: `{word}` is a variable, but the value is taken as css; \
+                 put Rust expressions in braces and css in quotes"
+            );
+            result = Err(syn::Error::new(word.span(), msg));
+        }
+    });
+    result
+}
+
+impl Style {
+    fn new(
+        signature: signature::Signature,
+        rules: rules::RuleList,
+        files: Vec<String>,
+    ) -> syn::Result<Self> {
+        let style = Style {
+            signature,
+            rules,
+            files,
+            prologue: Vec::new(),
+        };
+        style.check_classnames()?;
+        style.check_compositions()?;
+        Ok(style)
+    }
+
+    /// Parses the input of `make_styles_from_file!`: The path of a css file
+    /// relative to the crate root, followed by the signature
+    pub fn parse_from_file(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse::<syn::LitStr>()?;
+        input.parse::<Token![,]>()?;
+        let signature = input.parse::<signature::Signature>()?;
+
+        // Sass variables given as Rust expressions, e.g. `$primary: theme.primary`
+        let mut variables = Vec::new();
+        while input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            input.parse::<Token![$]>()?;
+            let name = input.parse::<syn::Ident>()?;
+            input.parse::<Token![:]>()?;
+            variables.push((name, input.parse::<syn::Expr>()?));
+        }
+
+        let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+        let file = Path::new(&root).join(path.value());
+
+        if path.value().ends_with(".scss") {
+            let (rules, files) = parse_scss_file(&file, &variables, path.span())?;
+            return Style::new(signature, rules, files);
+        }
+        if let Some((name, _)) = variables.first() {
+            let msg = "Variables are only available for SCSS files";
+            return Err(syn::Error::new(name.span(), msg));
+        }
+        let text = std::fs::read_to_string(&file).map_err(|err| {
+            let msg = format!("Cannot read `{}`: {err}", file.display());
+            syn::Error::new(path.span(), msg)
+        })?;
+
+        let rules = css::parse_text(&text, path.span(), Some(&path.value()), &[])?;
+        Style::new(signature, rules, vec![file.display().to_string()])
+    }
+}
+
+#[cfg(feature = "scss")]
+use scss::{parse as parse_scss, parse_file as parse_scss_file};
+
+#[cfg(not(feature = "scss"))]
+fn parse_scss(
+    _input: ParseStream,
+    span: proc_macro2::Span,
+) -> syn::Result<(rules::RuleList, Vec<String>)> {
+    Err(syn::Error::new(span, SCSS_DISABLED))
+}
+
+#[cfg(not(feature = "scss"))]
+fn parse_scss_file(
+    _file: &Path,
+    _variables: &[(syn::Ident, syn::Expr)],
+    span: proc_macro2::Span,
+) -> syn::Result<(rules::RuleList, Vec<String>)> {
+    Err(syn::Error::new(span, SCSS_DISABLED))
+}
+
+#[cfg(not(feature = "scss"))]
+const SCSS_DISABLED: &str = "SCSS requires the `scss` feature of css-in-rs";
+
 #[cfg(test)]
 mod tests {
     use quote::quote;
 
     use crate::data::rules::{
+        control::Item,
         header::{Header, Part},
-        Rule,
+        Rule, RuleBody,
     };
 
     use super::Style;
 
+    fn header_to_str(header: &Header) -> String {
+        use core::fmt::Write;
+        let mut result = String::new();
+
+        let parts = &header.parts;
+
+        for part in parts {
+            match part {
+                Part::Raw(s) => {
+                    write!(result, "raw'{}'", s)
+                }
+                Part::ClassName(s) => {
+                    write!(result, "classname'{}'", s)
+                }
+                Part::Keyframes(s) => {
+                    write!(result, "keyframes'{}'", s)
+                }
+                Part::Expr(expr) => {
+                    write!(result, "expr'{}'", expr)
+                }
+            }
+            .unwrap();
+        }
+
+        result
+    }
+
+    /// Unwraps an item which is not control flow
+    fn plain<T>(item: &Item<T>) -> &T {
+        match item {
+            Item::Plain(plain) => plain,
+            Item::Control(_) => unreachable!(),
+        }
+    }
+
     #[test]
     fn simple() {
         let input = quote! {
@@ -65,30 +403,9 @@ mod tests {
             }
         };
 
-        let header_to_str = |header: &Header| {
-            use core::fmt::Write;
-            let mut result = String::new();
-
-            let parts = &header.parts;
-
-            for part in parts {
-                match part {
-                    Part::Raw(s) => {
-                        write!(result, "raw'{}'", s)
-                    }
-                    Part::ClassName(s) => {
-                        write!(result, "classname'{}'", s)
-                    }
-                }
-                .unwrap();
-            }
-
-            result
-        };
-
         let style = syn::parse2::<Style>(input).unwrap();
         let rules = style.rules.rules;
-        let rules: Vec<&Rule> = rules.iter().collect();
+        let rules: Vec<&Rule> = rules.iter().map(plain).collect();
         let headers: Vec<&Header> = rules.iter().map(|r| &r.header).collect();
 
         assert_eq!(headers.len(), 3);
@@ -100,12 +417,12 @@ mod tests {
         {
             let rule = rules[2];
             let entries = match &rule.body {
-                crate::data::rules::RuleBody::Normal { entries } => entries,
+                crate::data::rules::RuleBody::Normal { entries, .. } => entries,
                 _ => unreachable!(),
             };
             assert_eq!(entries.len(), 2);
 
-            let mut entries = entries.iter();
+            let mut entries = entries.iter().map(plain);
 
             let entry = entries.next().unwrap();
             assert_eq!(entry.property, "background-color");
@@ -114,4 +431,649 @@ mod tests {
             assert_eq!(entry.property, "12%");
         }
     }
+
+    #[test]
+    fn pseudo_suffix() {
+        let input = quote! {
+            (_theme: MyTheme) -> MyClasses {
+                item:hover {},
+                item::before {},
+                item:not(:disabled) {},
+                item:nth-child(2n+1)::-webkit-scrollbar {},
+            }
+        };
+
+        let style = syn::parse2::<Style>(input).unwrap();
+        let headers: Vec<String> = style
+            .rules
+            .rules
+            .iter()
+            .map(|r| header_to_str(&plain(r).header))
+            .collect();
+
+        assert_eq!(
+            headers,
+            [
+                "raw'.'classname'item'raw':hover'",
+                "raw'.'classname'item'raw'::before'",
+                "raw'.'classname'item'raw':not(:disabled)'",
+                "raw'.'classname'item'raw':nth-child(2n+1)::-webkit-scrollbar'",
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_property() {
+        let input = quote! {
+            (_theme: MyTheme) -> MyClasses {
+                my_class {
+                    backgroud_color: "red",
+                },
+            }
+        };
+
+        let err = syn::parse2::<Style>(input).err().unwrap().to_string();
+        assert!(err.starts_with("Unknown css property `backgroud-color`"));
+        assert!(err.contains("did you mean `background_color`?"));
+
+        let input = quote! {
+            (_theme: MyTheme) -> MyClasses {
+                my_class {
+                    _webkit_box_orient: "vertical",
+                    "backgroud-color": "red",
+                },
+            }
+        };
+        assert!(syn::parse2::<Style>(input).is_ok());
+    }
+
+    #[test]
+    fn invalid_value() {
+        let error = |entry: proc_macro2::TokenStream| {
+            let input = quote! {
+                (theme: MyTheme) -> MyClasses {
+                    my_class { #entry },
+                }
+            };
+            syn::parse2::<Style>(input).err().map(|err| err.to_string())
+        };
+
+        assert_eq!(
+            error(quote!(display: "flexx")).unwrap(),
+            "Invalid value `flexx` for css property `display`; did you mean `flex`?"
+        );
+        assert!(error(quote!(color: "#ggg"))
+            .unwrap()
+            .starts_with("Invalid hex color"));
+        assert!(error(quote!(width: "10pz"))
+            .unwrap()
+            .starts_with("Unknown unit `pz`"));
+        assert!(error(quote!(width: "10"))
+            .unwrap()
+            .starts_with("Missing unit"));
+
+        assert!(error(quote!(display: "inline flex")).is_none());
+        assert!(error(quote!(display: theme.display)).is_none());
+        assert!(error(quote!(color: "rgba(0, 0, 0, 0.5)")).is_none());
+        assert!(error(quote!(margin: "0 auto -1.5em")).is_none());
+        assert!(error(quote!("display": "flexx")).is_none());
+        assert!(error(quote!(display: "none !important")).is_none());
+        assert!(error(quote!(color: "red !important")).is_none());
+        assert!(error(quote!(position: "-webkit-sticky")).is_none());
+        assert!(error(quote!(color: "-webkit-link")).is_none());
+        assert!(error(quote!(display: "flexx !important"))
+            .unwrap()
+            .starts_with("Invalid value `flexx`"));
+    }
+
+    #[test]
+    fn keyword_enums() {
+        let error = |entry: proc_macro2::TokenStream| {
+            let input = quote! {
+                (theme: MyTheme) -> MyClasses {
+                    my_class { #entry },
+                }
+            };
+            syn::parse2::<Style>(input).err().map(|err| err.to_string())
+        };
+
+        assert!(error(quote!(display: keywords::Display::Flex)).is_none());
+        assert!(error(quote!(display: css_in_rs::keywords::Display::InlineBlock)).is_none());
+        assert!(error(quote!(overflow_y: keywords::Overflow::Auto)).is_none());
+        assert!(error(quote!(display: MyEnum::Flex)).is_none());
+
+        // Enums of the same name defined elsewhere
+        assert!(error(quote!(display: Position::Absolute)).is_none());
+        assert!(error(quote!(inset: my::Position::Top)).is_none());
+
+        assert_eq!(
+            error(quote!(display: keywords::Position::Absolute)).unwrap(),
+            "`Position` is meant for css property `position`, not `display`; use `Display` instead"
+        );
+        assert_eq!(
+            error(quote!(display: ::css_in_rs::keywords::Display::Flexx)).unwrap(),
+            "`Display::Flexx` is not a keyword of css property `display`; did you mean `Display::Flex`?"
+        );
+        assert_eq!(
+            error(quote!(margin: keywords::Display::Flex)).unwrap(),
+            "`Display` is meant for css property `display`, not `margin`"
+        );
+    }
+
+    #[test]
+    fn invalid_selector() {
+        let error = |selector: &str| {
+            let input = quote! {
+                (_theme: MyTheme) -> MyClasses {
+                    #selector {},
+                }
+            };
+            syn::parse2::<Style>(input).err().unwrap().to_string()
+        };
+
+        assert_eq!(
+            error("div. span"),
+            "Expected a classname after `.`\n  div. span\n     ^\nhelp: classnames may only contain letters, digits, `-` and `_`"
+        );
+        assert_eq!(
+            error("a:not(.b"),
+            "Unbalanced brackets: `(` is never closed\n  a:not(.b\n       ^\nhelp: add a matching `)`"
+        );
+        assert!(error("a]").starts_with("Unbalanced brackets: unexpected `]`"));
+        assert!(error(".1a").starts_with("Classnames must not start with a digit"));
+        assert_eq!(
+            error(r".md\:flex"),
+            "Classnames must not contain escapes or non-ascii characters\n  .md\\:flex\n  ^^^^^^^^^\nhelp: use `:global(.md\\:flex)` to keep it as it is; it is not scoped"
+        );
+        assert!(error("a.café")
+            .starts_with("Classnames must not contain escapes or non-ascii characters"));
+    }
+
+    #[test]
+    fn selector_tokenizer() {
+        let header = |selector: &str| {
+            let input = quote! {
+                (_theme: MyTheme) -> MyClasses {
+                    #selector {},
+                }
+            };
+            let style = syn::parse2::<Style>(input).unwrap();
+            let rule = plain(style.rules.rules.first().unwrap());
+            header_to_str(&rule.header)
+        };
+
+        assert_eq!(
+            header(r#"a.link[href$=".pdf"]"#),
+            r#"raw'a.'classname'link'raw'[href$=".pdf"]'"#
+        );
+        assert_eq!(header("12.5%"), "raw'12.5%'");
+        assert_eq!(
+            header(r":global(.w-1\/2) > .x"),
+            r"raw'.w-1\/2 > .'classname'x'"
+        );
+        assert_eq!(
+            header("h1.title:not(.active)"),
+            "raw'h1.'classname'title'raw':not(.'classname'active'raw')'"
+        );
+        assert_eq!(
+            header(".a /* .b */ [data-x='.c']"),
+            "raw'.'classname'a'raw' /* .b */ [data-x='.c']'"
+        );
+    }
+
+    #[test]
+    fn kebab_case() {
+        let input = quote! {
+            (_theme: MyTheme) -> MyClasses {
+                ".btn-primary:hover" {},
+                "@keyframes fade-in" {},
+            }
+        };
+
+        let style = syn::parse2::<Style>(input).unwrap();
+        let classnames: Vec<String> = style
+            .get_classnames()
+            .iter()
+            .map(|ident| ident.to_string())
+            .collect();
+        assert_eq!(classnames, ["btn_primary", "fade_in"]);
+
+        let input = quote! {
+            (_theme: MyTheme) -> MyClasses {
+                ".a-b" {},
+                a_b {},
+            }
+        };
+
+        let err = syn::parse2::<Style>(input).err().unwrap().to_string();
+        assert_eq!(
+            err,
+            "The classnames `a-b` and `a_b` collide; both are mapped to the member `a_b`"
+        );
+
+        let input = quote! {
+            (_theme: MyTheme) -> MyClasses {
+                spin {},
+                "@keyframes spin" {},
+            }
+        };
+
+        let err = syn::parse2::<Style>(input).err().unwrap().to_string();
+        assert_eq!(
+            err,
+            "The classname `spin` collides with `@keyframes spin`; both are mapped to the member `spin`"
+        );
+    }
+
+    #[test]
+    fn global() {
+        let input = quote! {
+            (_theme: MyTheme) -> MyClasses {
+                ":global(.dark-mode) .panel" {},
+                "@global" {
+                    "body.dark-mode .widget" {
+                        "&:hover" {},
+                    },
+                },
+            }
+        };
+
+        let style = syn::parse2::<Style>(input).unwrap();
+        let rules: Vec<&Rule> = style.rules.rules.iter().map(plain).collect();
+        assert_eq!(
+            header_to_str(&rules[0].header),
+            "raw'.dark-mode .'classname'panel'"
+        );
+
+        let crate::data::rules::RuleBody::Global { children } = &rules[1].body else {
+            unreachable!()
+        };
+        let child = plain(children.first().unwrap());
+        assert_eq!(header_to_str(&child.header), "raw'body.dark-mode .widget'");
+
+        let classnames: Vec<String> = style
+            .get_classnames()
+            .iter()
+            .map(|ident| ident.to_string())
+            .collect();
+        assert_eq!(classnames, ["panel"]);
+    }
+
+    #[test]
+    fn at_rule_declarations() {
+        let input = quote! {
+            (_theme: MyTheme) -> MyClasses {
+                "@import url(\"x.css\")",
+                "@font-face" {
+                    font_family: "Foo",
+                    src: "url(foo.woff2)",
+                },
+                "@page :first" {
+                    margin: "1in",
+                    size: "A4",
+                },
+            }
+        };
+
+        let style = syn::parse2::<Style>(input).unwrap();
+        let rules: Vec<&Rule> = style.rules.rules.iter().map(plain).collect();
+        assert!(matches!(
+            rules[0].body,
+            crate::data::rules::RuleBody::Statement
+        ));
+        let crate::data::rules::RuleBody::Declarations { entries } = &rules[1].body else {
+            unreachable!()
+        };
+        assert_eq!(plain(&entries[1]).property, "src");
+        assert!(matches!(
+            rules[2].body,
+            crate::data::rules::RuleBody::Declarations { .. }
+        ));
+
+        let input = quote! {
+            (_theme: MyTheme) -> MyClasses {
+                "@font-face" {
+                    font_familly: "Foo",
+                },
+            }
+        };
+        let err = syn::parse2::<Style>(input).err().unwrap();
+        assert!(err.to_string().starts_with(
+            "Unknown descriptor `font-familly` for `@font-face`; did you mean `font_family`?"
+        ));
+
+        let input = quote! {
+            (_theme: MyTheme) -> MyClasses {
+                "@import url(x.css)" {},
+            }
+        };
+        let err = syn::parse2::<Style>(input).err().unwrap();
+        assert_eq!(err.to_string(), "`@import` must not have a block");
+
+        let error = |rules: proc_macro2::TokenStream| {
+            let input = quote! {
+                (_theme: MyTheme) -> MyClasses { #rules }
+            };
+            syn::parse2::<Style>(input).err().map(|err| err.to_string())
+        };
+        assert_eq!(
+            error(quote!(button { "@import url(x.css)", color: "red" })).unwrap(),
+            "`@import` is not allowed within style rules"
+        );
+        assert_eq!(
+            error(quote!("@media print" { "@charset \"utf-8\"", })).unwrap(),
+            "`@charset` is only allowed at the top level, not within at-rules"
+        );
+        assert!(error(quote!("@media print" { "@layer a, b", })).is_none());
+        assert_eq!(
+            error(quote!(a { "@keyframes spin" { "0%" { opacity: 0 } } })).unwrap(),
+            "`@keyframes` is only allowed at the top level or within at-rules"
+        );
+        assert_eq!(
+            error(quote!(a { "@font-face" { font_family: "Foo" } })).unwrap(),
+            "`@font-face` is only allowed at the top level or within at-rules"
+        );
+        assert!(error(quote!("@media print" { "@keyframes spin" {} })).is_none());
+    }
+
+    #[test]
+    fn interpolation() {
+        let parse = |selector: &str| {
+            let input = quote! {
+                (theme: MyTheme) -> MyClasses {
+                    #selector {},
+                }
+            };
+            syn::parse2::<Style>(input).map(|style| {
+                let rule = plain(style.rules.rules.first().unwrap());
+                header_to_str(&rule.header)
+            })
+        };
+
+        assert_eq!(
+            parse("@media (min-width: {theme.md}px)").unwrap(),
+            "raw'@media (min-width: 'expr'theme . md'raw'px)'"
+        );
+        assert_eq!(
+            parse(".item[data-id=\"{ID}\"]:global(.icon-{name})").unwrap(),
+            "raw'.'classname'item'raw'[data-id=\"'expr'ID'raw'\"].icon-'expr'name'"
+        );
+        assert_eq!(parse(".a {{}}").unwrap(), "raw'.'classname'a'raw' {}'");
+
+        let error = |selector: &str| parse(selector).err().unwrap().to_string();
+        assert!(error(".btn-{size}").starts_with("Classnames must not contain expressions"));
+        assert!(error("@media {theme.md").starts_with("Unterminated `{`"));
+        assert!(error("@media {1 +}").starts_with("Expected a Rust expression"));
+        assert!(error("@media }").starts_with("Unmatched `}`"));
+    }
+
+    #[test]
+    fn css_values() {
+        let parse = |entries: proc_macro2::TokenStream| {
+            let input = quote! {
+                (theme: MyTheme) -> MyClasses {
+                    my_class { #entries },
+                }
+            };
+            let style = syn::parse2::<Style>(input).unwrap();
+            let rule = plain(style.rules.rules.first().unwrap());
+            let entries = match &rule.body {
+                crate::data::rules::RuleBody::Normal { entries, .. } => entries,
+                _ => unreachable!(),
+            };
+            entries
+                .iter()
+                .map(plain)
+                .map(|entry| match &entry.value {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(value),
+                        ..
+                    }) => value.value(),
+                    value => format!("expr'{}'", quote!(#value)),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            parse(quote! {
+                display: flex,
+                justify_content: space-between,
+                margin: 0 -1px auto,
+                font_family: "Open Sans", Arial, sans-serif,
+                background: rgba(0, 0, 0, 0.5) url("a.png"),
+                width: calc(100% - 2 * var(--gap)),
+                grid_area: 1 / 3,
+                position: -webkit-sticky,
+            }),
+            [
+                "flex",
+                "space-between",
+                "0 -1px auto",
+                "\"Open Sans\", Arial, sans-serif",
+                "rgba(0, 0, 0, 0.5) url(\"a.png\")",
+                "calc(100% - 2 * var(--gap))",
+                "1 / 3",
+                "-webkit-sticky",
+            ]
+        );
+
+        // Single identifiers are only css if they are keywords of the property
+        assert_eq!(
+            parse(quote! {
+                color: red,
+                width: auto,
+                overflow: inherit,
+                background_size: cover,
+                width: w,
+                color: PRIMARY,
+                margin: -gap,
+                "x-custom": flex,
+            }),
+            [
+                "red",
+                "auto",
+                "inherit",
+                "cover",
+                "expr'w'",
+                "expr'PRIMARY'",
+                "expr'- gap'",
+                "expr'flex'",
+            ]
+        );
+
+        // `quote!` would interpolate `#fff`
+        let hash = "color: #fff, background: #1e90ff".parse().unwrap();
+        assert_eq!(parse(hash), ["#fff", "#1e90ff"]);
+
+        assert_eq!(
+            parse(quote! {
+                color: theme.color,
+                z_index: 10,
+                opacity: {theme.opacity},
+                border: 1px solid {theme.color},
+            }),
+            [
+                "expr'theme . color'",
+                "expr'10'",
+                "expr'{ theme . opacity }'",
+                "expr':: core :: format_args ! (\"1px solid {}\" , theme . color)'",
+            ]
+        );
+
+        // Braces after a value are interpolations, not nested rules
+        assert_eq!(
+            parse(quote! {
+                transition: opacity {theme.d}ms, transform {theme.d}ms,
+                border: solid {theme.color},
+                outline: solid {color},
+                icon { margin: 0 },
+                a:hover { color: red },
+            }),
+            [
+                "expr':: core :: format_args ! (\"opacity {}ms, transform {}ms\" , theme . d , theme . d)'",
+                "expr':: core :: format_args ! (\"solid {}\" , theme . color)'",
+                "expr':: core :: format_args ! (\"solid {}\" , color)'",
+            ]
+        );
+
+        // Identifiers within css values must not be variables
+        let error = |input: proc_macro2::TokenStream| {
+            let input = quote!((theme: MyTheme) -> MyClasses { #input });
+            syn::parse2::<Style>(input).err().map(|err| err.to_string())
+        };
+        assert!(error(quote!(a {
+            grid_area: header / main,
+            padding: a - b
+        }))
+        .is_none());
+        assert!(error(quote!(let gap = 4; a { padding: {gap - 1} })).is_none());
+        assert!(error(quote!(let gap = 4; a { padding: gap - border }))
+            .unwrap()
+            .starts_with("Ambiguous value: `gap` is a variable"));
+        assert!(
+            error(quote!(let (total, n) = (1, 2); a { flex_grow: total / n }))
+                .unwrap()
+                .starts_with("Ambiguous value: `total` is a variable")
+        );
+    }
+
+    #[test]
+    fn plain_css() {
+        // Parsed from a string, since whitespace is recovered from the
+        // positions of the tokens, which `quote!` does not provide
+        let input = r#"
+            (theme: MyTheme) -> MyClasses css {
+                @import url("base.css");
+                .btn-primary:hover, .panel .label {
+                    background-color: #fff;
+                    margin: 0 auto !important
+                }
+                @media (max-width: 600px) {
+                    .panel { display: none; }
+                }
+                .panel {
+                    --gap: 4px;
+                    border: 1px solid {theme.color};
+                    &:focus { color: red; }
+                }
+            }
+        "#;
+
+        let style = syn::parse_str::<Style>(input).unwrap();
+        let rules = style.rules.rules;
+        let rules: Vec<&Rule> = rules.iter().map(plain).collect();
+        let headers: Vec<String> = rules.iter().map(|r| header_to_str(&r.header)).collect();
+        assert_eq!(
+            headers,
+            [
+                "raw'@import url(\"base.css\")'",
+                "raw'.'classname'btn-primary'raw':hover, .'classname'panel'raw' .'classname'label'",
+                "raw'@media (max-width: 600px)'",
+                "raw'.'classname'panel'",
+            ]
+        );
+
+        let entries = |rule: &Rule| match &rule.body {
+            RuleBody::Normal { entries, .. } => entries
+                .iter()
+                .map(plain)
+                .map(|entry| {
+                    let value = &entry.value;
+                    let important = if entry.important { " !important" } else { "" };
+                    format!("{}: {}{important}", entry.property, quote!(#value))
+                })
+                .collect::<Vec<_>>(),
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            entries(rules[1]),
+            [
+                "background-color: \"#fff\"",
+                "margin: \"0 auto\" !important"
+            ]
+        );
+        assert_eq!(
+            entries(rules[3]),
+            [
+                "--gap: \"4px\"",
+                "border: :: core :: format_args ! (\"1px solid {}\" , theme . color)"
+            ]
+        );
+
+        let error = |input: &str| syn::parse_str::<Style>(input).err().unwrap().to_string();
+        assert!(error("(t: T) -> C css { .a { colour: red; } }")
+            .starts_with("Unknown css property `colour`"));
+        assert!(error("(t: T) -> C css { .a { red } }").starts_with("Expected a declaration"));
+        assert!(
+            syn::parse_str::<Style>("(t: T) -> C css { .a { \"text-box-trim\": both; } }").is_ok()
+        );
+
+        // Without positions, whitespace cannot be recovered
+        let input = quote!((t: T) -> C css { .a .b { color: red; } });
+        let error = syn::parse2::<Style>(input).err().unwrap().to_string();
+        assert!(error.starts_with("Plain css needs the positions of its tokens"));
+    }
+
+    #[test]
+    fn composes() {
+        let error = |rules: proc_macro2::TokenStream| {
+            let input = quote! {
+                (theme: MyTheme) -> MyClasses { #rules }
+            };
+            syn::parse2::<Style>(input).err().map(|err| err.to_string())
+        };
+
+        assert!(error(quote!(a { composes: b c }, b {}, c {})).is_none());
+        assert!(error(quote!(a { composes: active from global })).is_none());
+        assert!(error(quote!(a { composes: b }))
+            .unwrap()
+            .starts_with("Unknown class `b` in `composes`"));
+        assert!(error(quote!(a { composes: b from "b.css" }, b {}))
+            .unwrap()
+            .starts_with("Composing classes from `\"b.css\"` is not supported"));
+        assert!(error(quote!(a { "&:hover" { composes: b } }, b {}))
+            .unwrap()
+            .starts_with("`composes` is only allowed in rules with a single class selector"));
+        assert!(error(quote!(a { if theme.x { composes: b } }, b {}))
+            .unwrap()
+            .starts_with("`composes` is only allowed in rules with a single class selector"));
+    }
+
+    #[test]
+    fn css_file() {
+        let span = proc_macro2::Span::call_site();
+        let error = |text: &str| {
+            crate::data::css::parse_text(text, span, Some("button.css"), &[])
+                .err()
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(
+            error(".a {\n  color: red;\n  margin 0;\n}"),
+            "Expected a declaration like `color: red;` or a rule like `.item { ... }`\n  \
+            --> button.css:3:3"
+        );
+        assert_eq!(
+            error(".a { colour: red; }"),
+            "Unknown css property `colour`; did you mean `color`?\n\
+            Use a string literal to skip this check, i.e. `\"colour\": ...`\n  \
+            --> button.css:1:6"
+        );
+        assert!(error(".a {\n  text-box-trim: both;\n}")
+            .starts_with("Unknown css property `text-box-trim`"));
+        assert!(error(".a {\n  text-box-trim: both;\n}").ends_with("--> button.css:2:3"));
+        assert!(
+            error("@font-face {\n  src: url(a.woff2);\n  colour: red;\n}")
+                .ends_with("--> button.css:3:3")
+        );
+
+        // Quoted properties are not checked
+        let text = ".a { \"text-box-trim\": both; }";
+        let rules = crate::data::css::parse_text(text, span, Some("button.css"), &[]).unwrap();
+        rules.for_each_entry(&mut |entry| {
+            assert_eq!(entry.property, "text-box-trim");
+            assert!(!entry.checked);
+        });
+    }
 }
@@ -0,0 +1,553 @@
+//! Plain css syntax, e.g. `.button:hover { background-color: #fff; }`. The
+//! css is translated into the usual syntax of `make_styles!` (selectors as
+//! string literals, entries separated by commas) and parsed as such, so it
+//! ends up as the same rules and entries, with the same checks.
+
+use std::ops::Range;
+
+use proc_macro2::{Delimiter, LineColumn, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+use syn::parse::{Parse, ParseStream, Parser};
+
+use super::rules::{
+    entry::{self, strip_important, Entry},
+    header::Header,
+    RuleList,
+};
+
+/// Parses a body of plain css given as tokens
+pub fn parse(input: ParseStream) -> syn::Result<RuleList> {
+    let source = Source::from_tokens(input.parse()?)?;
+    let tokens = source.translate(0..source.text.len(), None)?;
+    syn::parse2(tokens)
+}
+
+/// Parses css which is not given as tokens, e.g. read from a file. Errors
+/// are reported at `span`; if `file` is given, the message contains the
+/// position within it. `exprs` are Rust expressions by placeholders; each
+/// placeholder within the text is replaced by its expression, like `{expr}`.
+pub fn parse_text(
+    text: &str,
+    span: Span,
+    file: Option<&str>,
+    exprs: &[(String, TokenStream)],
+) -> syn::Result<RuleList> {
+    let mut source = Source {
+        text: String::new(),
+        spans: Vec::new(),
+        groups: Vec::new(),
+        file: file.map(str::to_owned),
+        span,
+        last: None,
+    };
+
+    let mut rest = text;
+    loop {
+        let next = exprs
+            .iter()
+            .filter_map(|(placeholder, expr)| Some((rest.find(placeholder)?, placeholder, expr)))
+            .min_by_key(|(idx, _, _)| *idx);
+        let Some((idx, placeholder, expr)) = next else {
+            break;
+        };
+
+        source.text.push_str(&rest[..idx]);
+        source.groups.push((source.text.len(), expr.clone()));
+        source.text.push_str("{}");
+        rest = &rest[idx + placeholder.len()..];
+    }
+    source.text.push_str(rest);
+
+    let tokens = source.translate(0..source.text.len(), None)?;
+    syn::parse2(tokens).map_err(|err| match file {
+        Some(file) => syn::Error::new(err.span(), format!("{err}\n  --> {file}")),
+        None => err,
+    })
+}
+
+/// Css source text. Each token remembers its position within the text, so
+/// errors can be reported at the right place.
+pub struct Source {
+    text: String,
+    /// Offsets of the tokens within `text`, with their spans
+    spans: Vec<(usize, Span)>,
+    /// Brace groups by the offset of their `{`. Within values, they contain
+    /// Rust expressions.
+    groups: Vec<(usize, TokenStream)>,
+    /// The file the text was read from
+    file: Option<String>,
+    /// The span of text without tokens
+    span: Span,
+    /// The end of the previous token
+    last: Option<LineColumn>,
+}
+
+impl Source {
+    /// Reconstructs css source text from tokens. Whitespace is significant
+    /// in css (e.g. `.a .b` vs `.a.b`), so it is recovered from the
+    /// positions of the tokens. Without positions, this fails instead of
+    /// guessing.
+    pub fn from_tokens(tokens: TokenStream) -> syn::Result<Self> {
+        if let Some(span) = unlocated(tokens.clone()) {
+            let msg = "Plain css needs the positions of its tokens to recover whitespace, \
+                but they are not available here. This requires Rust 1.88 or later; \
+                otherwise use the usual syntax of `make_styles!`";
+            return Err(syn::Error::new(span, msg));
+        }
+
+        let mut source = Source {
+            text: String::new(),
+            spans: Vec::new(),
+            groups: Vec::new(),
+            file: None,
+            span: Span::call_site(),
+            last: None,
+        };
+        source.push_tokens(tokens);
+        Ok(source)
+    }
+
+    #[cfg(feature = "scss")]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Brace groups containing Rust expressions, with their ranges
+    /// (including the braces)
+    #[cfg(feature = "scss")]
+    pub fn exprs(&self) -> Vec<(Range<usize>, TokenStream)> {
+        let exprs = self.groups.iter().filter_map(|(offset, _)| {
+            let expr = self.expr(*offset)?;
+            let close = self.matching_brace(*offset, self.text.len()).ok()?;
+            Some((*offset..close + 1, expr))
+        });
+        exprs.collect()
+    }
+
+    fn push_tokens(&mut self, tokens: TokenStream) {
+        for token in tokens {
+            match token {
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::Brace => ("{", "}"),
+                        Delimiter::None => {
+                            self.push_tokens(group.stream());
+                            continue;
+                        }
+                    };
+
+                    let offset = self.push(open, group.span_open());
+                    if group.delimiter() == Delimiter::Brace {
+                        self.groups.push((offset, group.stream()));
+                    }
+                    self.push_tokens(group.stream());
+                    self.push(close, group.span_close());
+                }
+                TokenTree::Ident(ident) => {
+                    self.push(&ident.to_string(), ident.span());
+                }
+                TokenTree::Literal(lit) => {
+                    self.push(&lit.to_string(), lit.span());
+                }
+                TokenTree::Punct(punct) => {
+                    self.push(&punct.as_char().to_string(), punct.span());
+                }
+            }
+        }
+    }
+
+    /// Appends the text of a token and returns its offset
+    fn push(&mut self, text: &str, span: Span) -> usize {
+        if self.last.is_some_and(|end| end != span.start()) {
+            self.text.push(' ');
+        }
+
+        let offset = self.text.len();
+        self.text.push_str(text);
+        self.spans.push((offset, span));
+        self.last = Some(span.end());
+        offset
+    }
+
+    /// The span of the token at the given offset
+    fn span_at(&self, offset: usize) -> Span {
+        let idx = self.spans.partition_point(|(start, _)| *start <= offset);
+        match idx {
+            0 => self.span,
+            idx => self.spans[idx - 1].1,
+        }
+    }
+
+    fn error(&self, offset: usize, msg: impl std::fmt::Display) -> syn::Error {
+        let Some(file) = &self.file else {
+            return syn::Error::new(self.span_at(offset), msg);
+        };
+
+        let before = &self.text[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = before.len() - before.rfind('\n').map_or(0, |idx| idx + 1) + 1;
+        let msg = format!("{msg}\n  --> {file}:{line}:{column}");
+        syn::Error::new(self.span, msg)
+    }
+
+    /// The Rust expression within the braces at the given offset
+    fn expr(&self, offset: usize) -> Option<TokenStream> {
+        let idx = self
+            .groups
+            .binary_search_by_key(&offset, |(start, _)| *start);
+        let tokens = self.groups[idx.ok()?].1.clone();
+        syn::parse2::<syn::Expr>(tokens.clone()).ok()?;
+        Some(tokens)
+    }
+
+    /// Translates the items of a block, i.e. declarations, rules and
+    /// at-rule statements. `at_rule` is set for the body of an at-rule like
+    /// `@font-face`, which contains descriptors instead of properties.
+    fn translate(&self, range: Range<usize>, at_rule: Option<&str>) -> syn::Result<TokenStream> {
+        let mut result = TokenStream::new();
+        let mut pos = range.start;
+
+        loop {
+            pos = self.skip_whitespace(pos, range.end)?;
+            if pos >= range.end {
+                break;
+            }
+            pos = self.item(pos..range.end, at_rule, &mut result)?;
+        }
+
+        Ok(result)
+    }
+
+    /// Translates a single item and returns the offset after it. An item
+    /// starting with `property:` is a declaration, unless it is followed by
+    /// a block which is not a Rust expression, like in `a:hover { ... }`.
+    fn item(
+        &self,
+        range: Range<usize>,
+        at_rule: Option<&str>,
+        result: &mut TokenStream,
+    ) -> syn::Result<usize> {
+        let bytes = self.text.as_bytes();
+        let colon = property_len(&self.text[range.clone()]).map(|len| range.start + len);
+        let mut depth = 0usize;
+        let mut pos = range.start;
+
+        while pos < range.end {
+            match bytes[pos] {
+                b'"' | b'\'' => {
+                    pos = self.skip_string(pos, range.end)?;
+                    continue;
+                }
+                b'/' if bytes[pos..].starts_with(b"/*") => {
+                    pos = self.skip_comment(pos, range.end)?;
+                    continue;
+                }
+                b'(' | b'[' => depth += 1,
+                b')' | b']' => depth = depth.saturating_sub(1),
+                b';' if depth == 0 => {
+                    self.statement(range.start..pos, colon, at_rule, result)?;
+                    return Ok(pos + 1);
+                }
+                b'{' => {
+                    let close = self.matching_brace(pos, range.end)?;
+                    if colon.is_none() || self.expr(pos).is_none() {
+                        self.rule(range.start..pos, pos + 1..close, result)?;
+                        return Ok(close + 1);
+                    }
+                    pos = close;
+                }
+                b'}' => return Err(self.error(pos, "Unmatched `}`")),
+                _ => {}
+            }
+            pos += 1;
+        }
+
+        self.statement(range.start..range.end, colon, at_rule, result)?;
+        Ok(range.end)
+    }
+
+    /// Translates a rule like `.button:hover { ... }` or `@media ... { ... }`
+    fn rule(
+        &self,
+        header: Range<usize>,
+        body: Range<usize>,
+        result: &mut TokenStream,
+    ) -> syn::Result<()> {
+        let span = self.span_at(header.start);
+        let text = collapse_whitespace(&self.text[header.clone()]);
+        let at_rule = text.strip_prefix('@').and_then(|name| {
+            let end = name
+                .find(|ch: char| !ch.is_alphanumeric() && ch != '-' && ch != '_')
+                .unwrap_or(name.len());
+            entry::has_descriptors(&name[..end]).then_some(&name[..end])
+        });
+
+        let lit = syn::LitStr::new(&text, span);
+        self.check(header.start, Header::parse, quote!(#lit))?;
+        let body = self.translate(body, at_rule)?;
+        result.extend(quote_spanned!(span => #lit { #body },));
+        Ok(())
+    }
+
+    /// Translates a declaration like `color: red` or an at-rule statement
+    /// like `@import url(...)`. `colon` is the offset of the colon after the
+    /// property, if there is one.
+    fn statement(
+        &self,
+        range: Range<usize>,
+        colon: Option<usize>,
+        at_rule: Option<&str>,
+        result: &mut TokenStream,
+    ) -> syn::Result<()> {
+        let text = self.text[range.clone()].trim();
+        let span = self.span_at(range.start);
+
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        if text.starts_with('@') {
+            let statement = syn::LitStr::new(&collapse_whitespace(text), span);
+            result.extend(quote_spanned!(span => #statement,));
+            return Ok(());
+        }
+
+        let Some(colon) = colon else {
+            let msg = "Expected a declaration like `color: red;` or a rule like `.item { ... }`";
+            return Err(self.error(range.start, msg));
+        };
+
+        let property = property(self.text[range.start..colon].trim(), span);
+        let (value, important) = strip_important(&self.text[colon + 1..range.end]);
+        let value = self.value(colon + 1..colon + 1 + value.len())?;
+        let important = important.then(|| quote_spanned!(span => !important));
+        let entry = quote_spanned!(span => #property: #value #important);
+        match at_rule {
+            Some(at_rule) => {
+                let parse = |input: ParseStream| Entry::parse_descriptor(input, at_rule);
+                self.check(range.start, parse, entry.clone())?;
+            }
+            None => self.check(range.start, Entry::parse, entry.clone())?,
+        }
+        result.extend(quote!(#entry,));
+        Ok(())
+    }
+
+    /// Text which is not given as tokens has no spans pointing into it, so
+    /// errors would only point to the macro call. Parses a translated item
+    /// right away instead, to report errors at its position in the file.
+    fn check<T>(
+        &self,
+        offset: usize,
+        parser: impl Parser<Output = T>,
+        tokens: TokenStream,
+    ) -> syn::Result<()> {
+        if self.file.is_some() {
+            parser
+                .parse2(tokens)
+                .map_err(|err| self.error(offset, err))?;
+        }
+        Ok(())
+    }
+
+    /// Translates a value into a string literal, or into `format_args!` if
+    /// it contains Rust expressions
+    fn value(&self, range: Range<usize>) -> syn::Result<TokenStream> {
+        let span = self.span_at(range.start);
+        let bytes = self.text.as_bytes();
+        let mut text = String::new();
+        let mut format_str = String::new();
+        let mut args = Vec::new();
+        let mut start = range.start;
+        let mut pos = range.start;
+
+        while pos < range.end {
+            match bytes[pos] {
+                b'"' | b'\'' => {
+                    pos = self.skip_string(pos, range.end)?;
+                    continue;
+                }
+                b'{' => {
+                    let close = self.matching_brace(pos, range.end)?;
+                    let literal = &self.text[start..pos];
+                    text.push_str(literal);
+                    format_str.push_str(&literal.replace('{', "{{").replace('}', "}}"));
+                    format_str.push_str("{}");
+                    args.push(self.expr(pos).expect("values only contain expressions"));
+                    pos = close + 1;
+                    start = pos;
+                    continue;
+                }
+                _ => {}
+            }
+            pos += 1;
+        }
+
+        let literal = &self.text[start..range.end];
+        text.push_str(literal);
+        format_str.push_str(&literal.replace('{', "{{").replace('}', "}}"));
+
+        if text.trim().is_empty() && args.is_empty() {
+            return Err(self.error(range.start, "Expected a value"));
+        }
+
+        if args.is_empty() {
+            let value = syn::LitStr::new(&collapse_whitespace(&text), span);
+            return Ok(quote!(#value));
+        }
+
+        let format_str = syn::LitStr::new(&collapse_whitespace(&format_str), span);
+        Ok(quote_spanned!(span => ::core::format_args!(#format_str #(, #args)*)))
+    }
+
+    fn skip_whitespace(&self, mut pos: usize, end: usize) -> syn::Result<usize> {
+        let bytes = self.text.as_bytes();
+        while pos < end {
+            if bytes[pos].is_ascii_whitespace() {
+                pos += 1;
+            } else if bytes[pos..].starts_with(b"/*") {
+                pos = self.skip_comment(pos, end)?;
+            } else {
+                break;
+            }
+        }
+        Ok(pos)
+    }
+
+    /// Returns the offset after the string starting at `pos`
+    fn skip_string(&self, pos: usize, end: usize) -> syn::Result<usize> {
+        let bytes = self.text.as_bytes();
+        let quote = bytes[pos];
+        let mut idx = pos + 1;
+        while idx < end {
+            match bytes[idx] {
+                b'\\' => idx += 1,
+                ch if ch == quote => return Ok(idx + 1),
+                _ => {}
+            }
+            idx += 1;
+        }
+        Err(self.error(pos, "Unterminated string"))
+    }
+
+    /// Returns the offset after the comment starting at `pos`
+    fn skip_comment(&self, pos: usize, end: usize) -> syn::Result<usize> {
+        match self.text[pos + 2..end].find("*/") {
+            Some(len) => Ok(pos + 2 + len + 2),
+            None => Err(self.error(pos, "Unterminated comment")),
+        }
+    }
+
+    /// Returns the offset of the `}` matching the `{` at `pos`
+    fn matching_brace(&self, pos: usize, end: usize) -> syn::Result<usize> {
+        let bytes = self.text.as_bytes();
+        let mut depth = 0usize;
+        let mut idx = pos;
+        while idx < end {
+            match bytes[idx] {
+                b'"' | b'\'' => {
+                    idx = self.skip_string(idx, end)?;
+                    continue;
+                }
+                b'/' if bytes[idx..].starts_with(b"/*") => {
+                    idx = self.skip_comment(idx, end)?;
+                    continue;
+                }
+                b'{' => depth += 1,
+                b'}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(idx);
+                    }
+                }
+                _ => {}
+            }
+            idx += 1;
+        }
+        Err(self.error(pos, "Unterminated `{`"))
+    }
+}
+
+/// Returns the span of the first token without a position, e.g. because
+/// the compiler does not provide them. Their spans are empty.
+fn unlocated(tokens: TokenStream) -> Option<Span> {
+    let is_empty = |span: Span| span.start() == span.end();
+    tokens.into_iter().find_map(|token| match token {
+        TokenTree::Group(group) if is_empty(group.span_open()) => Some(group.span_open()),
+        TokenTree::Group(group) => unlocated(group.stream()),
+        token => is_empty(token.span()).then(|| token.span()),
+    })
+}
+
+/// Returns the length of `property:` if the text starts with a property
+/// name followed by a colon. The name may be quoted, e.g. `"text-box-trim":`
+fn property_len(text: &str) -> Option<usize> {
+    let end = match text.strip_prefix('"') {
+        Some(quoted) => quoted.find('"')? + 2,
+        None => {
+            let name = text.trim_start_matches('-');
+            if !name.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_') {
+                return None;
+            }
+            text.find(|ch: char| !ch.is_ascii_alphanumeric() && ch != '-' && ch != '_')
+                .unwrap_or(text.len())
+        }
+    };
+    let colon = end + (text.len() - end - text[end..].trim_start().len());
+    (text[colon..].starts_with(':') && !text[colon..].starts_with("::")).then_some(colon)
+}
+
+/// Properties are turned into identifiers, so they are checked like
+/// properties written as identifiers. Custom properties like `--gap` are
+/// kept as string literals. Quoted properties are not checked, like string
+/// literals in `make_styles!`.
+fn property(name: &str, span: Span) -> TokenStream {
+    if let Ok(mut lit) = syn::parse_str::<syn::LitStr>(name) {
+        lit.set_span(span);
+        return quote!(#lit);
+    }
+
+    let is_plain = !name.starts_with("--")
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '-');
+
+    if let Ok(mut ident) = syn::parse_str::<syn::Ident>(&name.replace('-', "_")) {
+        if is_plain {
+            ident.set_span(span);
+            return quote!(#ident);
+        }
+    }
+
+    let name = syn::LitStr::new(name, span);
+    quote!(#name)
+}
+
+/// Replaces all runs of whitespace outside of strings by a single space
+fn collapse_whitespace(text: &str) -> String {
+    let mut result = String::new();
+    let mut quote = None;
+    let mut escaped = false;
+
+    for ch in text.trim().chars() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if ch == '\\' => escaped = true,
+            Some(q) if ch == q => quote = None,
+            Some(_) => {}
+            None if ch == '"' || ch == '\'' => quote = Some(ch),
+            None if ch.is_whitespace() => {
+                if !result.ends_with(' ') {
+                    result.push(' ');
+                }
+                continue;
+            }
+            None => {}
+        }
+        result.push(ch);
+    }
+
+    result
+}
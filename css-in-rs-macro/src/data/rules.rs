@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use proc_macro2::Span;
 use syn::{
@@ -9,109 +9,485 @@ use syn::{
 
 use crate::output::{Output, ToOutput};
 
+pub mod control;
 pub mod entry;
 pub mod header;
 
+use control::Item;
+
+#[derive(Clone)]
 pub enum RuleBody {
     AtRule {
-        children: Punctuated<Rule, syn::token::Comma>,
+        children: Punctuated<Item<Rule>, syn::token::Comma>,
+    },
+    /// Rules within `@global { ... }`. Their classnames are not scoped
+    Global {
+        children: Punctuated<Item<Rule>, syn::token::Comma>,
     },
+    /// At-rules like `@font-face` which contain descriptors instead of rules
+    Declarations { entries: Vec<Item<entry::Entry>> },
+    /// At-rules without a block, e.g. `@import` or `@layer a, b`
+    Statement,
     Normal {
-        entries: Punctuated<entry::Entry, syn::token::Comma>,
+        entries: Vec<Item<entry::Entry>>,
+        children: Vec<Item<Rule>>,
     },
 }
 
+/// An element of the body of a style rule
+#[derive(Clone)]
+enum Member {
+    Entry(Box<entry::Entry>),
+    Rule(Rule),
+}
+
+impl Parse for Member {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        match Rule::peek(input) {
+            true => Ok(Member::Rule(Rule::parse_in(input, Position::StyleRule)?)),
+            false => Ok(Member::Entry(input.parse()?)),
+        }
+    }
+}
+
 impl RuleBody {
-    fn collect_classnames(&self, result: &mut BTreeMap<String, Span>) {
+    fn for_each_header(&self, f: &mut dyn FnMut(&header::Header)) {
         match self {
-            RuleBody::AtRule { children } => {
+            RuleBody::AtRule { children } | RuleBody::Global { children } => {
+                for child in children {
+                    child.for_each(&mut |child| child.for_each_header(f));
+                }
+            }
+            RuleBody::Normal { children, .. } => {
+                for child in children {
+                    child.for_each(&mut |child| child.for_each_header(f));
+                }
+            }
+            RuleBody::Declarations { .. } | RuleBody::Statement => {}
+        }
+    }
+
+    fn for_each_entry(&self, f: &mut dyn FnMut(&entry::Entry)) {
+        match self {
+            RuleBody::AtRule { children } | RuleBody::Global { children } => {
+                for child in children {
+                    child.for_each(&mut |child| child.body.for_each_entry(f));
+                }
+            }
+            RuleBody::Declarations { entries } => {
+                for entry in entries {
+                    entry.for_each(&mut |entry| f(entry));
+                }
+            }
+            RuleBody::Normal { entries, children } => {
+                for entry in entries {
+                    entry.for_each(&mut |entry| f(entry));
+                }
                 for child in children {
-                    child.collect_classnames(result);
+                    child.for_each(&mut |child| child.body.for_each_entry(f));
                 }
             }
-            RuleBody::Normal { .. } => {}
+            RuleBody::Statement => {}
+        }
+    }
+
+    /// Parses the body of a style rule: A list of entries, mixed with
+    /// nested rules (e.g. `"&:hover" { ... }` or `"@media ..." { ... }`).
+    /// Control flow may contain both; it is split into control flow around
+    /// the entries and control flow around the nested rules.
+    fn parse_normal(input: ParseStream) -> syn::Result<Self> {
+        let mut members = Vec::new();
+
+        while !input.is_empty() {
+            members.push(Item::parse(input, &Member::parse)?);
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        let entries = Item::filter_map(&members, &|member| match member {
+            Member::Entry(entry) => Some((**entry).clone()),
+            Member::Rule(_) => None,
+        });
+        let children = Item::filter_map(&members, &|member| match member {
+            Member::Rule(rule) => Some(rule.clone()),
+            Member::Entry(_) => None,
+        });
+
+        Ok(RuleBody::Normal { entries, children })
+    }
+
+    /// Parses the body of an at-rule like `@font-face`: A list of
+    /// descriptors of the at-rule `name`
+    fn parse_declarations(input: ParseStream, name: &str) -> syn::Result<Self> {
+        let mut entries = Vec::new();
+
+        while !input.is_empty() {
+            let parse_descriptor = |input: ParseStream| entry::Entry::parse_descriptor(input, name);
+            entries.push(Item::parse(input, &parse_descriptor)?);
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
         }
+
+        Ok(RuleBody::Declarations { entries })
     }
 }
 
+/// At-rules which may (or must) be used without a block
+const STATEMENTS: &[&str] = &["charset", "import", "layer", "namespace"];
+
+/// Where a rule is placed, which determines what it may contain
+#[derive(Clone, Copy, PartialEq)]
+enum Position {
+    /// At the top level of the style (possibly within control flow)
+    TopLevel,
+    /// Within an at-rule like `@media` or `@global`
+    AtRule,
+    /// Within a style rule
+    StyleRule,
+}
+
+#[derive(Clone)]
 pub struct Rule {
     pub header: header::Header,
     pub body: RuleBody,
 }
 
 impl Rule {
-    fn collect_classnames(&self, result: &mut BTreeMap<String, Span>) {
-        self.header.collect_classnames(result);
-        self.body.collect_classnames(result);
+    /// Calls `f` for the header of this rule and of all nested rules
+    fn for_each_header(&self, f: &mut dyn FnMut(&header::Header)) {
+        f(&self.header);
+        self.body.for_each_header(f);
     }
-}
 
-pub struct RuleList {
-    pub rules: Punctuated<Rule, syn::token::Comma>,
-}
-
-impl RuleList {
-    pub fn collect_classnames(&self, result: &mut BTreeMap<String, Span>) {
-        for rule in &self.rules {
-            rule.collect_classnames(result);
+    /// Checks whether the input starts with a (nested) rule instead of
+    /// an entry, i.e. with a valid header followed by a block, or with an
+    /// at-rule without a block like `"@import ..."`
+    fn peek(input: ParseStream) -> bool {
+        if input.peek(syn::LitStr) {
+            // Do not validate the selector here; invalid selectors should be
+            // reported as such, not as invalid entries
+            if input.peek2(syn::token::Brace) {
+                return true;
+            }
+            let lit = input.fork().parse::<syn::LitStr>().expect("peeked");
+            return !input.peek2(Token![:]) && lit.value().trim_start().starts_with('@');
         }
+
+        header::Header::peek_ident(input)
     }
-}
 
-impl Parse for Rule {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
+    /// Parses a rule at the given position. Within a style rule, at-rules
+    /// like `@media` contain entries for the parent selector instead of a
+    /// list of rules.
+    fn parse_in(input: ParseStream, position: Position) -> syn::Result<Self> {
+        let nested = position == Position::StyleRule;
         let header = input.parse::<header::Header>()?;
+        let name = header.at_rule_name().unwrap_or_default().to_string();
+
+        if STATEMENTS.contains(&name.as_str()) {
+            if !input.peek(syn::token::Brace) {
+                // `@layer a, b` may be used within at-rules like `@media`;
+                // the others only at the top of the stylesheet
+                let msg = match position {
+                    Position::TopLevel => None,
+                    Position::AtRule if name == "layer" => None,
+                    Position::AtRule => Some(format!(
+                        "`@{name}` is only allowed at the top level, not within at-rules"
+                    )),
+                    Position::StyleRule => {
+                        Some(format!("`@{name}` is not allowed within style rules"))
+                    }
+                };
+                if let Some(msg) = msg {
+                    return Err(syn::Error::new(header.span, msg));
+                }
+                let body = RuleBody::Statement;
+                return Ok(Rule { header, body });
+            }
+            if name != "layer" {
+                let msg = format!("`@{name}` must not have a block");
+                return Err(syn::Error::new(header.span, msg));
+            }
+        }
 
         let content;
         syn::braced!(content in input);
 
-        let body = match header.at_rule {
+        // Unlike `@media`, these do not apply to the parent selector
+        let standalone = entry::has_descriptors(&name) || name.ends_with("keyframes");
+        if nested && standalone {
+            let msg = format!("`@{name}` is only allowed at the top level or within at-rules");
+            return Err(syn::Error::new(header.span, msg));
+        }
+
+        if entry::has_descriptors(&name) {
+            let body = RuleBody::parse_declarations(&content, &name)?;
+            return Ok(Rule { header, body });
+        }
+
+        if header.is_global() {
+            if nested {
+                let msg = "`@global` is only allowed at the top level or within at-rules";
+                return Err(syn::Error::new(header.span, msg));
+            }
+
+            let mut children = content.parse_terminated(Rule::parse_child, Token![,])?;
+            for child in &mut children {
+                child.for_each_mut(&mut Rule::unscope);
+            }
+            let body = RuleBody::Global { children };
+            return Ok(Rule { header, body });
+        }
+
+        let body = match header.at_rule && !nested {
             true => {
-                let children = content.parse_terminated(Rule::parse, Token![,])?;
+                let children = content.parse_terminated(Rule::parse_child, Token![,])?;
                 RuleBody::AtRule { children }
             }
-            false => {
-                let entries = content.parse_terminated(entry::Entry::parse, Token![,])?;
-                RuleBody::Normal { entries }
-            }
+            false => RuleBody::parse_normal(&content)?,
         };
 
         let rule = Rule { header, body };
 
         Ok(rule)
     }
-}
 
-impl Parse for RuleList {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
-        let rules = input.parse_terminated(Rule::parse, Token![,])?;
-        Ok(RuleList { rules })
+    /// Collects the classes composed into the class of this rule via
+    /// `composes: ...`. Like in CSS Modules, this is only allowed in rules
+    /// with a single class selector, not within nested rules, at-rules or
+    /// control flow.
+    fn collect_compositions(
+        &self,
+        result: &mut BTreeMap<String, Vec<entry::Composed>>,
+    ) -> syn::Result<()> {
+        let classname = match self.header.parts.as_slice() {
+            [header::Part::Raw(dot), header::Part::ClassName(name)] if dot == "." => name,
+            _ => return self.check_no_compositions(),
+        };
+        let RuleBody::Normal { entries, children } = &self.body else {
+            return self.check_no_compositions();
+        };
+
+        for entry in entries {
+            match entry {
+                Item::Plain(entry) => {
+                    if let Some(composed) = entry.composed() {
+                        result
+                            .entry(classname.clone())
+                            .or_default()
+                            .extend(composed);
+                    }
+                }
+                Item::Control(_) => {
+                    entry.try_for_each(&mut check_no_composition)?;
+                }
+            }
+        }
+        for child in children {
+            child.try_for_each(&mut Rule::check_no_compositions)?;
+        }
+        Ok(())
     }
-}
 
-impl ToOutput for RuleBody {
-    fn append(&self, result: &mut Output) {
-        match self {
+    /// Reports `composes: ...` entries anywhere within this rule
+    fn check_no_compositions(&self) -> syn::Result<()> {
+        match &self.body {
+            RuleBody::AtRule { children } | RuleBody::Global { children } => {
+                for child in children {
+                    child.try_for_each(&mut Rule::check_no_compositions)?;
+                }
+            }
+            RuleBody::Normal { entries, children } => {
+                for entry in entries {
+                    entry.try_for_each(&mut check_no_composition)?;
+                }
+                for child in children {
+                    child.try_for_each(&mut Rule::check_no_compositions)?;
+                }
+            }
+            RuleBody::Declarations { .. } | RuleBody::Statement => {}
+        }
+        Ok(())
+    }
+
+    /// Parses a rule or control flow around rules
+    fn parse_item(input: ParseStream) -> syn::Result<Item<Rule>> {
+        Item::parse(input, &Rule::parse)
+    }
+
+    /// Like [Rule::parse_item], but within an at-rule
+    fn parse_child(input: ParseStream) -> syn::Result<Item<Rule>> {
+        Item::parse(input, &|input| Rule::parse_in(input, Position::AtRule))
+    }
+
+    /// Turns all classnames in this rule into plain text, i.e. they
+    /// won't be scoped
+    fn unscope(&mut self) {
+        self.header.unscope();
+        match &mut self.body {
+            RuleBody::AtRule { children } | RuleBody::Global { children } => {
+                for child in children {
+                    child.for_each_mut(&mut Rule::unscope);
+                }
+            }
+            RuleBody::Normal { children, .. } => {
+                for child in children {
+                    child.for_each_mut(&mut Rule::unscope);
+                }
+            }
+            RuleBody::Declarations { .. } | RuleBody::Statement => {}
+        }
+    }
+
+    /// Appends this rule. Nested rules are flattened: Their selectors are
+    /// resolved against the selector of the `parent` rule.
+    fn append_nested(&self, result: &mut Output, parent: Option<&header::Header>) {
+        match &self.body {
             RuleBody::AtRule { children } => {
+                self.header.append(result);
+                result.format_str.push_str(" {{\n");
                 for child in children {
                     child.append(result);
                 }
+                result.format_str.push_str("}}\n");
             }
-            RuleBody::Normal { entries } => {
-                for entry in entries {
-                    entry.append(result);
+            RuleBody::Global { children } => {
+                for child in children {
+                    child.append(result);
+                }
+            }
+            RuleBody::Declarations { entries } => {
+                append_block(result, &self.header, entries);
+            }
+            RuleBody::Statement => {
+                self.header.append(result);
+                result.format_str.push_str(";\n");
+            }
+            RuleBody::Normal { entries, children } if self.header.at_rule => {
+                // At-rule nested within a style rule; its entries belong
+                // to the parent selector
+                let parent = parent.expect("nested at-rules always have a parent");
+
+                self.header.append(result);
+                result.format_str.push_str(" {{\n");
+                if !entries.is_empty() {
+                    append_block(result, parent, entries);
+                }
+                for child in children {
+                    child.append_with(result, &|child, result| {
+                        child.append_nested(result, Some(parent))
+                    });
+                }
+                result.format_str.push_str("}}\n");
+            }
+            RuleBody::Normal { entries, children } => {
+                let resolved;
+                let header = match parent {
+                    Some(parent) => {
+                        resolved = self.header.resolve(parent);
+                        &resolved
+                    }
+                    None => &self.header,
+                };
+
+                if !entries.is_empty() || children.is_empty() {
+                    append_block(result, header, entries);
+                }
+                for child in children {
+                    child.append_with(result, &|child, result| {
+                        child.append_nested(result, Some(header))
+                    });
                 }
             }
         }
     }
 }
 
+fn check_no_composition(entry: &entry::Entry) -> syn::Result<()> {
+    match entry.composed() {
+        Some(_) => Err(syn::Error::new(
+            entry.span(),
+            "`composes` is only allowed in rules with a single class selector like \
+            `.button { ... }`, not within nested rules, at-rules or control flow",
+        )),
+        None => Ok(()),
+    }
+}
+
+fn append_block(result: &mut Output, header: &header::Header, entries: &[Item<entry::Entry>]) {
+    header.append(result);
+    result.format_str.push_str(" {{\n");
+    for entry in entries {
+        entry.append(result);
+    }
+    result.format_str.push_str("}}\n");
+}
+
+pub struct RuleList {
+    pub rules: Punctuated<Item<Rule>, syn::token::Comma>,
+}
+
+impl RuleList {
+    /// Calls `f` for the headers of all rules, including nested rules
+    pub fn for_each_header(&self, f: &mut dyn FnMut(&header::Header)) {
+        for rule in &self.rules {
+            rule.for_each(&mut |rule| rule.for_each_header(f));
+        }
+    }
+
+    /// Calls `f` for all entries, including those of nested rules
+    pub fn for_each_entry(&self, f: &mut dyn FnMut(&entry::Entry)) {
+        for rule in &self.rules {
+            rule.for_each(&mut |rule| rule.body.for_each_entry(f));
+        }
+    }
+
+    pub fn collect_classnames(&self, result: &mut BTreeMap<String, Span>) {
+        self.for_each_header(&mut |header| header.collect_classnames(result));
+    }
+
+    pub fn collect_keyframes(&self, result: &mut BTreeSet<String>) {
+        self.for_each_header(&mut |header| header.collect_keyframes(result));
+    }
+
+    /// Collects the classes composed into other classes via `composes: ...`,
+    /// by the name of the class they are composed into
+    pub fn collect_compositions(
+        &self,
+        result: &mut BTreeMap<String, Vec<entry::Composed>>,
+    ) -> syn::Result<()> {
+        for rule in &self.rules {
+            match rule {
+                Item::Plain(rule) => rule.collect_compositions(result)?,
+                Item::Control(_) => rule.try_for_each(&mut Rule::check_no_compositions)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Parse for Rule {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Rule::parse_in(input, Position::TopLevel)
+    }
+}
+
+impl Parse for RuleList {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let rules = input.parse_terminated(Rule::parse_item, Token![,])?;
+        Ok(RuleList { rules })
+    }
+}
+
 impl ToOutput for Rule {
     fn append(&self, result: &mut Output) {
-        self.header.append(result);
-        result.format_str.push_str(" {{\n");
-        self.body.append(result);
-        result.format_str.push_str("}}\n");
+        self.append_nested(result, None);
     }
 }
 
@@ -0,0 +1,236 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{parse::ParseStream, Token};
+
+use crate::output::{Output, ToOutput};
+
+/// An element of a list of rules or entries: Either a plain rule/entry, or
+/// Rust control flow (`if`, `match` or `for`) around a nested list
+#[derive(Clone)]
+pub enum Item<T> {
+    Plain(T),
+    Control(Control<T>),
+}
+
+/// Rust control flow around lists of rules or entries. It is represented by
+/// its branches, each consisting of the Rust tokens in front of a block and
+/// the content of that block:
+/// * `if a { .. } else if b { .. } else { .. }` has the heads `if a`,
+///   `else if b` and `else`
+/// * `for x in y { .. }` has a single head `for x in y`
+/// * `match x { A => { .. }, B => { .. } }` has the heads `A =>` and `B =>`;
+///   `match x` is stored separately
+#[derive(Clone)]
+pub struct Control<T> {
+    scrutinee: Option<TokenStream>,
+    branches: Vec<Branch<T>>,
+}
+
+#[derive(Clone)]
+struct Branch<T> {
+    head: TokenStream,
+    items: Vec<Item<T>>,
+}
+
+impl<T> Item<T> {
+    /// Parses either control flow or a plain element using `parse_plain`
+    pub fn parse(
+        input: ParseStream,
+        parse_plain: &dyn Fn(ParseStream) -> syn::Result<T>,
+    ) -> syn::Result<Self> {
+        match Control::<T>::peek(input) {
+            true => Ok(Item::Control(Control::parse(input, parse_plain)?)),
+            false => Ok(Item::Plain(parse_plain(input)?)),
+        }
+    }
+
+    /// Calls `f` for each plain element, including those within all
+    /// branches of the control flow
+    pub fn for_each(&self, f: &mut impl FnMut(&T)) {
+        match self {
+            Item::Plain(plain) => f(plain),
+            Item::Control(control) => {
+                for branch in &control.branches {
+                    branch.items.iter().for_each(|item| item.for_each(f));
+                }
+            }
+        }
+    }
+
+    /// Like [Item::for_each], but stops at the first error
+    pub fn try_for_each(&self, f: &mut impl FnMut(&T) -> syn::Result<()>) -> syn::Result<()> {
+        let mut result = Ok(());
+        self.for_each(&mut |plain| {
+            if result.is_ok() {
+                result = f(plain);
+            }
+        });
+        result
+    }
+
+    /// Like [Item::for_each], but for mutable access
+    pub fn for_each_mut(&mut self, f: &mut impl FnMut(&mut T)) {
+        match self {
+            Item::Plain(plain) => f(plain),
+            Item::Control(control) => {
+                for branch in &mut control.branches {
+                    branch
+                        .items
+                        .iter_mut()
+                        .for_each(|item| item.for_each_mut(f));
+                }
+            }
+        }
+    }
+
+    /// Keeps only the plain elements for which `f` returns something,
+    /// but keeps the control flow around them. Control flow which ends up
+    /// empty is dropped entirely.
+    pub fn filter_map<U>(items: &[Item<T>], f: &impl Fn(&T) -> Option<U>) -> Vec<Item<U>> {
+        let mut result = Vec::new();
+        for item in items {
+            match item {
+                Item::Plain(plain) => result.extend(f(plain).map(Item::Plain)),
+                Item::Control(control) => {
+                    let branches: Vec<Branch<U>> = control
+                        .branches
+                        .iter()
+                        .map(|branch| Branch {
+                            head: branch.head.clone(),
+                            items: Item::filter_map(&branch.items, f),
+                        })
+                        .collect();
+
+                    if branches.iter().any(|branch| !branch.items.is_empty()) {
+                        result.push(Item::Control(Control {
+                            scrutinee: control.scrutinee.clone(),
+                            branches,
+                        }));
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// Appends this item, using `f` to append plain elements
+    pub fn append_with(&self, result: &mut Output, f: &impl Fn(&T, &mut Output)) {
+        let control = match self {
+            Item::Plain(plain) => return f(plain, result),
+            Item::Control(control) => control,
+        };
+
+        let mut branches = TokenStream::default();
+        for Branch { head, items } in &control.branches {
+            let mut output = result.nested();
+            for item in items {
+                item.append_with(&mut output, f);
+            }
+            quote!(#head { #output }).to_tokens(&mut branches);
+        }
+
+        let statement = match &control.scrutinee {
+            Some(scrutinee) => quote!(#scrutinee { #branches }),
+            None => branches,
+        };
+        result.push_statement(statement);
+    }
+}
+
+impl<T> Control<T> {
+    pub fn peek(input: ParseStream) -> bool {
+        input.peek(Token![if]) || input.peek(Token![for]) || input.peek(Token![match])
+    }
+
+    fn parse(
+        input: ParseStream,
+        parse_plain: &dyn Fn(ParseStream) -> syn::Result<T>,
+    ) -> syn::Result<Self> {
+        let mut scrutinee = None;
+        let mut branches = Vec::new();
+
+        if input.peek(Token![if]) {
+            let mut head = TokenStream::default();
+            loop {
+                input.parse::<Token![if]>()?.to_tokens(&mut head);
+                let cond = syn::Expr::parse_without_eager_brace(input)?;
+                cond.to_tokens(&mut head);
+                let items = parse_block(input, parse_plain)?;
+                branches.push(Branch { head, items });
+
+                head = match input.parse::<Option<Token![else]>>()? {
+                    Some(token) => token.into_token_stream(),
+                    None => break,
+                };
+                if !input.peek(Token![if]) {
+                    let items = parse_block(input, parse_plain)?;
+                    branches.push(Branch { head, items });
+                    break;
+                }
+            }
+        } else if input.peek(Token![for]) {
+            let for_token = input.parse::<Token![for]>()?;
+            let pat = syn::Pat::parse_multi_with_leading_vert(input)?;
+            let in_token = input.parse::<Token![in]>()?;
+            let expr = syn::Expr::parse_without_eager_brace(input)?;
+            let head = quote!(#for_token #pat #in_token #expr);
+            let items = parse_block(input, parse_plain)?;
+            branches.push(Branch { head, items });
+        } else {
+            let match_token = input.parse::<Token![match]>()?;
+            let expr = syn::Expr::parse_without_eager_brace(input)?;
+            scrutinee = Some(quote!(#match_token #expr));
+
+            let content;
+            syn::braced!(content in input);
+            while !content.is_empty() {
+                let mut head =
+                    syn::Pat::parse_multi_with_leading_vert(&content)?.into_token_stream();
+                if let Some(if_token) = content.parse::<Option<Token![if]>>()? {
+                    let guard = content.parse::<syn::Expr>()?;
+                    quote!(#if_token #guard).to_tokens(&mut head);
+                }
+                content.parse::<Token![=>]>()?.to_tokens(&mut head);
+                let items = parse_block(&content, parse_plain)?;
+                branches.push(Branch { head, items });
+
+                if content.is_empty() {
+                    break;
+                }
+                content.parse::<Option<Token![,]>>()?;
+            }
+        }
+
+        Ok(Control {
+            scrutinee,
+            branches,
+        })
+    }
+}
+
+/// Parses a braced, comma separated list of items
+fn parse_block<T>(
+    input: ParseStream,
+    parse_plain: &dyn Fn(ParseStream) -> syn::Result<T>,
+) -> syn::Result<Vec<Item<T>>> {
+    let content;
+    syn::braced!(content in input);
+
+    let mut items = Vec::new();
+    while !content.is_empty() {
+        items.push(Item::parse(&content, parse_plain)?);
+
+        if content.is_empty() {
+            break;
+        }
+        content.parse::<Token![,]>()?;
+    }
+
+    Ok(items)
+}
+
+impl<T: ToOutput> ToOutput for Item<T> {
+    fn append(&self, result: &mut Output) {
+        self.append_with(result, &|plain, result| plain.append(result));
+    }
+}
@@ -1,39 +1,341 @@
-use quote::{quote, ToTokens};
-use syn::parse::{Parse, ParseStream};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+};
 
 use crate::output::{Output, ToOutput};
 
+pub mod keywords;
+mod properties;
+mod raw;
+mod suggest;
+mod units;
+mod values;
+
+pub use values::strip_important;
+
+#[derive(Clone)]
 pub struct Entry {
     pub property: String,
     pub value: syn::Expr,
+    /// Set for `color: red !important`
+    pub important: bool,
+    /// Set if the property is given as identifier. Entries with a property
+    /// given as string literal are not checked at all.
+    pub checked: bool,
+    /// Identifiers taken as css within the value, e.g. in `gap-border` or
+    /// `total / n`. They must not be variables of the prologue.
+    pub words: Vec<syn::Ident>,
 }
 
 impl Parse for Entry {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let property = {
-            if let Some(property) = input.parse::<syn::LitStr>().ok() {
-                property.value()
+        Entry::parse_in(input, None)
+    }
+}
+
+impl Entry {
+    /// Parses an entry of an at-rule like `@font-face`. These contain
+    /// descriptors instead of properties.
+    pub fn parse_descriptor(input: ParseStream, at_rule: &str) -> syn::Result<Self> {
+        Entry::parse_in(input, Some(at_rule))
+    }
+
+    fn parse_in(input: ParseStream, at_rule: Option<&str>) -> syn::Result<Self> {
+        let (property, checked) = {
+            if let Ok(property) = input.parse::<syn::LitStr>() {
+                (property.value(), false)
             } else {
-                let property = input.parse::<syn::Ident>()?;
-                property.to_string().replace('_', "-")
+                let ident = input.parse::<syn::Ident>()?;
+                let property = ident.to_string().replace('_', "-");
+                match at_rule {
+                    Some(at_rule) => check_descriptor(&ident, &property, at_rule)?,
+                    None => check_property(&ident, &property)?,
+                }
+                (property, true)
             }
         };
 
         input.parse::<syn::token::Colon>()?;
-        let value = input.parse::<syn::Expr>()?;
+        // Descriptors share names with properties, but not their syntax
+        let known = checked && at_rule.is_none();
+        let mut words = Vec::new();
+        let property_name = known.then_some(property.as_str());
+        let (value, important) = raw::parse_value(input, property_name, &mut words)?;
 
-        let entry = Entry { property, value };
+        if known && property != COMPOSES {
+            check_value(&property, &value)?;
+        }
+        if property == COMPOSES && at_rule.is_none() {
+            parse_composes(&value)?;
+        }
+
+        let entry = Entry {
+            property,
+            value,
+            important,
+            checked,
+            words,
+        };
 
         Ok(entry)
     }
+
+    /// Returns the composed classes if this is a `composes: ...` entry
+    pub fn composed(&self) -> Option<Vec<Composed>> {
+        if self.property != COMPOSES {
+            return None;
+        }
+        Some(parse_composes(&self.value).expect("checked while parsing"))
+    }
+
+    pub fn span(&self) -> Span {
+        self.value.span()
+    }
+
+    /// Appends the value of an `animation` or `animation-name` entry which is
+    /// given as a string literal. Names of keyframes defined in the same style
+    /// are replaced by their scoped names.
+    fn append_animation(&self, result: &mut Output) -> bool {
+        if self.property != "animation" && self.property != "animation-name" {
+            return false;
+        }
+
+        let value = match &self.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(value),
+                ..
+            }) => value.value(),
+            _ => return false,
+        };
+
+        let property = &self.property;
+        result.format_str.push_str("  {}: ");
+        quote!(, #property).to_tokens(&mut result.params);
+
+        let is_word_char = |ch: char| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-';
+        let mut rest = value.as_str();
+        while !rest.is_empty() {
+            let len = rest.find(|ch| !is_word_char(ch)).unwrap_or(rest.len());
+            let (word, remaining) = match len {
+                0 => rest.split_at(rest.chars().next().unwrap().len_utf8()),
+                len => rest.split_at(len),
+            };
+
+            if result.is_keyframes(word) {
+                result.push_classname(word);
+            } else {
+                result.push_str(word);
+            }
+            rest = remaining;
+        }
+
+        result.format_str.push_str(self.suffix());
+        true
+    }
+
+    /// The value to be written. Rust expressions are wrapped into a
+    /// type-level check, so keyword enums must match the property (see
+    /// [keywords::assert_enum]), and numbers get the default unit.
+    fn value_tokens(&self, value: &syn::Expr) -> TokenStream {
+        let literal = matches!(value, syn::Expr::Lit(_) | syn::Expr::Macro(_))
+            || units::number_literal(value).is_some();
+        if literal || !self.checked {
+            return units::apply(&self.property, value);
+        }
+
+        let check = keywords::assert_enum(&self.property, value.span());
+        let with_unit = units::apply_to_ref(&self.property, value.span());
+        quote_spanned! {value.span()=>
+            match &(#value) {
+                value => {
+                    #check
+                    #with_unit
+                }
+            }
+        }
+    }
+
+    /// The end of the declaration following the value
+    fn suffix(&self) -> &'static str {
+        match self.important {
+            true => " !important;\n",
+            false => ";\n",
+        }
+    }
+}
+
+/// A class composed into another one via `composes: ...` (CSS Modules)
+#[derive(Clone)]
+pub struct Composed {
+    pub name: String,
+    /// Set for `composes: name from global`; the name is not scoped
+    pub global: bool,
+    pub span: Span,
+}
+
+/// `composes` is not a css property. It adds other classes to the class
+/// of the rule, i.e. the member of the generated struct contains their
+/// names as well.
+const COMPOSES: &str = "composes";
+
+/// Parses the value of `composes`, i.e. `a b` or `a b from global`
+fn parse_composes(value: &syn::Expr) -> syn::Result<Vec<Composed>> {
+    let syn::Expr::Lit(syn::ExprLit {
+        lit: syn::Lit::Str(value),
+        ..
+    }) = value
+    else {
+        let msg = "`composes` expects classnames, e.g. `composes: base`";
+        return Err(syn::Error::new(value.span(), msg));
+    };
+
+    let span = value.span();
+    let value = value.value();
+    let (names, global) = match value.split_once(" from ") {
+        Some((names, "global")) => (names, true),
+        Some((_, source)) => {
+            let msg = format!(
+                "Composing classes from `{source}` is not supported; only `from global` is"
+            );
+            return Err(syn::Error::new(span, msg));
+        }
+        None => (value.as_str(), false),
+    };
+
+    let mut result = Vec::new();
+    for name in names.split_whitespace() {
+        let valid = name.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_' || ch == '-')
+            && name
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-');
+        if !valid {
+            let msg = format!("Invalid classname `{name}` in `composes`");
+            return Err(syn::Error::new(span, msg));
+        }
+
+        let name = name.to_owned();
+        result.push(Composed { name, global, span });
+    }
+
+    if result.is_empty() {
+        let msg = "`composes` expects classnames, e.g. `composes: base`";
+        return Err(syn::Error::new(span, msg));
+    }
+    Ok(result)
+}
+
+/// Checks whether the given at-rule (without `@`) contains declarations,
+/// like `@font-face`, instead of nested rules
+pub fn has_descriptors(at_rule: &str) -> bool {
+    properties::has_descriptors(at_rule)
+}
+
+/// Properties given as identifiers are checked against a list of known
+/// css properties. String literals are not checked (neither the property
+/// nor its value); they can be used for experimental or otherwise unknown
+/// properties.
+fn check_property(ident: &syn::Ident, property: &str) -> syn::Result<()> {
+    if properties::is_known(property) || property == COMPOSES {
+        return Ok(());
+    }
+
+    let mut msg = format!("Unknown css property `{property}`");
+    if let Some(suggestion) = properties::suggest(property) {
+        let suggestion = suggestion.replace('-', "_");
+        msg.push_str(&format!("; did you mean `{suggestion}`?"));
+    }
+    msg.push_str(&format!(
+        "\nUse a string literal to skip this check, i.e. `\"{property}\": ...`"
+    ));
+
+    Err(syn::Error::new(ident.span(), msg))
+}
+
+/// Like [check_property], but for descriptors of at-rules like `@font-face`
+fn check_descriptor(ident: &syn::Ident, descriptor: &str, at_rule: &str) -> syn::Result<()> {
+    if properties::is_known_descriptor(at_rule, descriptor) {
+        return Ok(());
+    }
+
+    let mut msg = format!("Unknown descriptor `{descriptor}` for `@{at_rule}`");
+    if let Some(suggestion) = properties::suggest_descriptor(at_rule, descriptor) {
+        let suggestion = suggestion.replace('-', "_");
+        msg.push_str(&format!("; did you mean `{suggestion}`?"));
+    }
+    msg.push_str(&format!(
+        "\nUse a string literal to skip this check, i.e. `\"{descriptor}\": ...`"
+    ));
+
+    Err(syn::Error::new(ident.span(), msg))
+}
+
+/// Values given as string literals are checked for obvious errors, and
+/// keyword enums like `keywords::Display::Flex` must match the property. Other
+/// expressions (e.g. values taken from the theme) are not checked.
+fn check_value(property: &str, value: &syn::Expr) -> syn::Result<()> {
+    if let syn::Expr::Array(array) = value {
+        return array
+            .elems
+            .iter()
+            .try_for_each(|value| check_value(property, value));
+    }
+
+    if let syn::Expr::Lit(syn::ExprLit {
+        lit: syn::Lit::Str(value),
+        ..
+    }) = value
+    {
+        values::check(property, &value.value())
+            .map_err(|msg| syn::Error::new(value.span(), msg))?;
+    }
+
+    if let syn::Expr::Path(value) = value {
+        keywords::check_enum(property, &value.path)
+            .map_err(|msg| syn::Error::new_spanned(value, msg))?;
+    }
+
+    Ok(())
 }
 
 impl ToOutput for Entry {
     fn append(&self, result: &mut Output) {
+        // `[a, b]` repeats the declaration for each value, e.g. as fallbacks
+        if let syn::Expr::Array(array) = &self.value {
+            for value in &array.elems {
+                let entry = Entry {
+                    value: value.clone(),
+                    ..self.clone()
+                };
+                entry.append(result);
+            }
+            return;
+        }
+
+        if self.property == COMPOSES || self.append_animation(result) {
+            return;
+        }
+
         let property = &self.property;
-        let value = &self.value;
+        let suffix = self.suffix();
+
+        // `value?` omits the declaration if the value is `None`
+        if let syn::Expr::Try(syn::ExprTry { expr, .. }) = &self.value {
+            let format_str = format!("  {{}}: {{}}{suffix}");
+            let value = self.value_tokens(&syn::parse_quote!(value));
+            result.push_statement(quote! {
+                if let Some(value) = &(#expr) {
+                    let _ = write!(css, #format_str, #property, #value);
+                }
+            });
+            return;
+        }
 
-        result.format_str.push_str("  {}: {};\n");
+        let value = self.value_tokens(&self.value);
+        result.format_str.push_str("  {}: {}");
+        result.format_str.push_str(suffix);
         quote!(, #property, #value).to_tokens(&mut result.params);
     }
 }
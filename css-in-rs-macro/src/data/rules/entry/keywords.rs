@@ -0,0 +1,266 @@
+//! Keywords of css properties which only take a closed set of keywords.
+//! For each of these properties, `css_in_rs::keywords` contains an enum.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+
+/// Properties which only accept keywords (besides the global keywords
+/// like `inherit`), sorted by property name. The keywords are separated
+/// by whitespace
+const KEYWORDS: &[(&str, &str)] = &[
+    (
+        "align-content",
+        "normal start center end flex-start flex-end baseline space-between space-around space-evenly stretch",
+    ),
+    (
+        "align-items",
+        "normal stretch center start end flex-start flex-end self-start self-end baseline anchor-center",
+    ),
+    (
+        "align-self",
+        "auto normal stretch center start end flex-start flex-end self-start self-end baseline anchor-center",
+    ),
+    ("backface-visibility", "visible hidden"),
+    ("border-collapse", "collapse separate"),
+    ("box-decoration-break", "slice clone"),
+    ("box-sizing", "content-box border-box"),
+    ("caption-side", "top bottom"),
+    ("clear", "none left right both inline-start inline-end"),
+    ("content-visibility", "visible auto hidden"),
+    (
+        "cursor",
+        "auto default none context-menu help pointer progress wait cell crosshair text vertical-text alias copy move no-drop not-allowed grab grabbing all-scroll col-resize row-resize n-resize e-resize s-resize w-resize ne-resize nw-resize se-resize sw-resize ew-resize ns-resize nesw-resize nwse-resize zoom-in zoom-out",
+    ),
+    ("direction", "ltr rtl"),
+    (
+        "display",
+        "block inline inline-block flex inline-flex grid inline-grid flow-root none contents table inline-table table-row-group table-header-group table-footer-group table-row table-cell table-column-group table-column table-caption list-item ruby ruby-text flow run-in math",
+    ),
+    ("empty-cells", "show hide"),
+    ("flex-direction", "row row-reverse column column-reverse"),
+    ("flex-wrap", "nowrap wrap wrap-reverse"),
+    ("float", "left right none inline-start inline-end"),
+    ("font-kerning", "auto normal none"),
+    ("image-rendering", "auto smooth high-quality pixelated crisp-edges"),
+    ("isolation", "auto isolate"),
+    (
+        "justify-content",
+        "normal start center end flex-start flex-end left right space-between space-around space-evenly stretch",
+    ),
+    (
+        "justify-items",
+        "normal stretch center start end flex-start flex-end self-start self-end left right baseline anchor-center",
+    ),
+    (
+        "justify-self",
+        "auto normal stretch center start end flex-start flex-end self-start self-end left right baseline anchor-center",
+    ),
+    ("list-style-position", "inside outside"),
+    (
+        "mix-blend-mode",
+        "normal multiply screen overlay darken lighten color-dodge color-burn hard-light soft-light difference exclusion hue saturation color luminosity plus-darker plus-lighter",
+    ),
+    ("object-fit", "fill contain cover none scale-down"),
+    ("overflow", "visible hidden clip scroll auto"),
+    ("overflow-wrap", "normal break-word anywhere"),
+    ("overflow-x", "visible hidden clip scroll auto"),
+    ("overflow-y", "visible hidden clip scroll auto"),
+    (
+        "pointer-events",
+        "auto none visiblepainted visiblefill visiblestroke visible painted fill stroke all bounding-box",
+    ),
+    ("position", "static relative absolute fixed sticky"),
+    ("resize", "none both horizontal vertical block inline"),
+    ("scroll-behavior", "auto smooth"),
+    ("table-layout", "auto fixed"),
+    ("text-align", "start end left right center justify justify-all match-parent"),
+    ("text-overflow", "clip ellipsis"),
+    ("text-transform", "none capitalize uppercase lowercase full-width full-size-kana"),
+    (
+        "touch-action",
+        "auto none pan-x pan-left pan-right pan-y pan-up pan-down pinch-zoom manipulation",
+    ),
+    ("transform-style", "flat preserve-3d"),
+    ("user-select", "auto text none contain all"),
+    ("visibility", "visible hidden collapse"),
+    ("white-space", "normal pre nowrap pre-wrap pre-line break-spaces"),
+    ("word-break", "normal break-all keep-all break-word auto-phrase"),
+    ("writing-mode", "horizontal-tb vertical-rl vertical-lr sideways-rl sideways-lr"),
+];
+
+/// Keywords which are valid for any property
+pub const GLOBAL: &[&str] = &["inherit", "initial", "unset", "revert", "revert-layer"];
+
+/// Keywords which only modify other keywords, e.g. `safe center`
+pub const MODIFIERS: &[&str] = &["safe", "unsafe", "first", "last", "legacy"];
+
+/// Returns the keywords accepted by the given property, if it only
+/// accepts keywords
+pub fn get(property: &str) -> Option<impl Iterator<Item = &'static str> + Clone> {
+    KEYWORDS
+        .binary_search_by_key(&property, |(property, _)| property)
+        .ok()
+        .map(|idx| KEYWORDS[idx].1.split_whitespace())
+}
+
+/// Converts a property or keyword to PascalCase, e.g. `flex-direction`
+/// to `FlexDirection`. This is how the enums and their variants are named.
+fn pascal_case(s: &str) -> String {
+    let mut result = String::new();
+    for word in s.split('-') {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            result.push(first.to_ascii_uppercase());
+            result.extend(chars);
+        }
+    }
+    result
+}
+
+/// Checks a value like `keywords::Display::Flex`, given as a path. Only
+/// paths into `css_in_rs::keywords` (or `keywords`) are checked; others may
+/// refer to enums of the same name defined elsewhere.
+pub fn check_enum(property: &str, path: &syn::Path) -> Result<(), String> {
+    let segments: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+    let (name, variant) = match segments.as_slice() {
+        [module, name, variant] if module == "keywords" => (name, variant),
+        [krate, module, name, variant] if krate == "css_in_rs" && module == "keywords" => {
+            (name, variant)
+        }
+        _ => return Ok(()),
+    };
+
+    let Some((enum_property, keywords)) = KEYWORDS
+        .iter()
+        .find(|(enum_property, _)| pascal_case(enum_property) == *name)
+    else {
+        return Ok(());
+    };
+
+    // Enums of properties with the same keywords are interchangeable,
+    // e.g. `Overflow` can be used for `overflow-x`
+    let same_keywords = KEYWORDS
+        .iter()
+        .any(|(other, other_keywords)| *other == property && other_keywords == keywords);
+    if !same_keywords {
+        let mut msg =
+            format!("`{name}` is meant for css property `{enum_property}`, not `{property}`");
+        if get(property).is_some() {
+            msg.push_str(&format!("; use `{}` instead", pascal_case(property)));
+        }
+        return Err(msg);
+    }
+
+    let variants = keywords.split_whitespace().map(pascal_case);
+    if variants.clone().any(|other| other == *variant) {
+        return Ok(());
+    }
+
+    let variants: Vec<String> = variants.collect();
+    let mut msg = format!("`{name}::{variant}` is not a keyword of css property `{property}`");
+    if let Some(suggestion) = super::suggest::closest(variant, variants.iter().map(String::as_str))
+    {
+        msg.push_str(&format!("; did you mean `{name}::{suggestion}`?"));
+    }
+    Err(msg)
+}
+
+/// The marker type of the property within `css_in_rs::keywords::property`,
+/// given to the type-level check of keyword enums
+fn marker(property: &str) -> syn::Ident {
+    let name = match get(property) {
+        Some(_) => property.replace('-', "_"),
+        None => "other".to_owned(),
+    };
+    syn::Ident::new(&name, Span::call_site())
+}
+
+/// A type-level check of a reference to a value given as Rust expression,
+/// bound to `value`: If it is a keyword enum, it must belong to the property.
+/// Unlike [check_enum], this also covers enums which are imported, aliased or
+/// taken from the theme.
+pub fn assert_enum(property: &str, span: Span) -> TokenStream {
+    let marker = marker(property);
+    quote_spanned! {span=>
+        // Only one of the traits is used, depending on the type
+        #[allow(unused_imports)]
+        use ::css_in_rs::keywords::check::{Keyword as _, Other as _};
+        (&::css_in_rs::keywords::check::Value(value))
+            .check::<::css_in_rs::keywords::property::#marker>();
+    }
+}
+
+/// Generates the definitions of all keyword enums
+pub fn enums() -> TokenStream {
+    let markers = KEYWORDS.iter().map(|(property, _)| {
+        let name = marker(property);
+        let doc = format!("The css property `{property}`");
+        quote! {
+            #[doc = #doc]
+            pub struct #name;
+        }
+    });
+
+    let enums = KEYWORDS.iter().map(|(property, keywords)| {
+        let name = syn::Ident::new(&pascal_case(property), Span::call_site());
+        let doc = format!("Keywords of the css property `{property}`");
+        // Enums of properties with the same keywords are interchangeable
+        let properties = KEYWORDS
+            .iter()
+            .filter(|(_, other_keywords)| other_keywords == keywords)
+            .map(|(property, _)| marker(property));
+        let keywords: Vec<&str> = keywords.split_whitespace().collect();
+        let variants: Vec<syn::Ident> = keywords
+            .iter()
+            .map(|keyword| syn::Ident::new(&pascal_case(keyword), Span::call_site()))
+            .collect();
+        let variant_docs = keywords.iter().map(|keyword| format!("`{keyword}`"));
+
+        quote! {
+            #[doc = #doc]
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+            pub enum #name {
+                #(
+                    #[doc = #variant_docs]
+                    #variants,
+                )*
+            }
+
+            impl #name {
+                /// Returns the css keyword
+                pub fn as_str(self) -> &'static str {
+                    match self {
+                        #(#name::#variants => #keywords,)*
+                    }
+                }
+            }
+
+            impl ::core::fmt::Display for #name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.write_str(self.as_str())
+                }
+            }
+
+            impl Keyword for #name {
+                const PROPERTY: &'static str = #property;
+            }
+
+            #(impl KeywordOf<property::#properties> for #name {})*
+        }
+    });
+
+    quote! {
+        #(#enums)*
+
+        /// Marker types for css properties, used to check that keyword
+        /// enums match the property
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        pub mod property {
+            #(#markers)*
+
+            /// Properties which do not only take keywords
+            pub struct other;
+        }
+    }
+}
@@ -0,0 +1,465 @@
+//! Values given as plain css tokens instead of Rust expressions, e.g.
+//! `margin: 5px 10px`. The css text is reconstructed at compile time. Since
+//! whitespace is not part of the tokens, it is inserted where css needs it.
+//! Rust expressions can be spliced in using braces: `border: 1px solid {color}`.
+
+use proc_macro2::{Delimiter, Group, Span, TokenStream, TokenTree};
+use quote::quote_spanned;
+use syn::{parse::ParseStream, Token};
+
+use super::{values, COMPOSES};
+use crate::data::rules::control::Control;
+
+/// Math functions; `+` and `-` within them are binary operators which need
+/// surrounding whitespace
+const MATH_FUNCTIONS: &[&str] = &["calc", "clamp", "max", "min"];
+
+/// Css functions which also happen to be valid Rust function calls
+const FUNCTIONS: &[&str] = &[
+    "attr",
+    "blur",
+    "brightness",
+    "calc",
+    "clamp",
+    "color",
+    "contrast",
+    "counter",
+    "env",
+    "grayscale",
+    "hsl",
+    "hsla",
+    "hwb",
+    "lab",
+    "lch",
+    "matrix",
+    "max",
+    "min",
+    "minmax",
+    "oklab",
+    "oklch",
+    "opacity",
+    "perspective",
+    "repeat",
+    "rgb",
+    "rgba",
+    "rotate",
+    "rotateX",
+    "rotateY",
+    "rotateZ",
+    "saturate",
+    "scale",
+    "scaleX",
+    "scaleY",
+    "sepia",
+    "skew",
+    "skewX",
+    "skewY",
+    "steps",
+    "translate",
+    "translateX",
+    "translateY",
+    "translateZ",
+    "url",
+    "var",
+];
+
+/// Suffixes of Rust number literals, e.g. `1.5f32`
+const RUST_SUFFIXES: &[&str] = &[
+    "", "f32", "f64", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64",
+    "u128", "usize",
+];
+
+/// Parses the value of an entry, followed by an optional `!important`.
+/// Values which are valid Rust expressions are taken as they are, unless
+/// they look like css (e.g. `flex`, `5px` or `space-between`). Everything
+/// else is parsed as css tokens. Css values are turned into string literals,
+/// or into `format_args!` if they contain Rust expressions. Returns the
+/// value, and whether it is marked `!important`.
+///
+/// A single identifier is only css if it is a keyword of the property, if
+/// known; otherwise it refers to a Rust variable or constant. Identifiers
+/// taken as css within larger values, e.g. `gap` in `gap-border` or `a` in
+/// `a / b`, are added to `words`, since they might be variables as well.
+pub fn parse_value(
+    input: ParseStream,
+    property: Option<&str>,
+    words: &mut Vec<syn::Ident>,
+) -> syn::Result<(syn::Expr, bool)> {
+    let span = input.span();
+    let mut tokens = Vec::new();
+    while !ends_value(input) {
+        tokens.push(input.parse::<TokenTree>()?);
+    }
+
+    let important = matches!(
+        tokens.as_slice(),
+        [.., TokenTree::Punct(bang), TokenTree::Ident(ident)]
+            if bang.as_char() == '!' && ident == "important"
+    );
+    if important {
+        tokens.truncate(tokens.len() - 2);
+    }
+
+    if tokens.is_empty() {
+        return Err(syn::Error::new(span, "Expected a value"));
+    }
+
+    if let [TokenTree::Group(group)] = tokens.as_slice() {
+        if group.delimiter() == Delimiter::Bracket {
+            return Ok((parse_fallbacks(group, property, words)?, important));
+        }
+    }
+
+    Ok((
+        parse_tokens(TokenStream::from_iter(tokens), span, property, words),
+        important,
+    ))
+}
+
+/// Takes the tokens of a value as a Rust expression, or as css
+fn parse_tokens(
+    tokens: TokenStream,
+    span: Span,
+    property: Option<&str>,
+    words: &mut Vec<syn::Ident>,
+) -> syn::Expr {
+    if let Ok(expr) = syn::parse2::<syn::Expr>(tokens.clone()) {
+        let mut idents = Vec::new();
+        let css = match single_ident(&expr) {
+            // `display: flex` is css, but `width: w` refers to a variable.
+            // `composes` takes names of classes.
+            Some(ident) => {
+                property == Some(COMPOSES) || values::is_keyword(property, &ident.to_string())
+            }
+            None => is_css(&expr, &mut idents),
+        };
+        if !css {
+            return expr;
+        }
+        words.extend(idents);
+    }
+
+    let mut css = Css::default();
+    css.push_tokens(tokens, false);
+    css.into_expr(span)
+}
+
+/// Parses fallback values like `[-webkit-box, flex]` into an array, which
+/// repeats the declaration for each of them. Each element is a value on its
+/// own, i.e. it is either a Rust expression or css.
+fn parse_fallbacks(
+    group: &Group,
+    property: Option<&str>,
+    words: &mut Vec<syn::Ident>,
+) -> syn::Result<syn::Expr> {
+    let mut values: Vec<Vec<TokenTree>> = vec![Vec::new()];
+    for token in group.stream() {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => values.push(Vec::new()),
+            _ => values.last_mut().unwrap().push(token),
+        }
+    }
+
+    // A trailing comma is fine
+    if values.len() > 1 && values.last().is_some_and(Vec::is_empty) {
+        values.pop();
+    }
+
+    let mut elems = Vec::new();
+    for value in values {
+        let Some(first) = value.first() else {
+            return Err(syn::Error::new(group.span(), "Expected a value"));
+        };
+        let span = first.span();
+        elems.push(parse_tokens(
+            TokenStream::from_iter(value),
+            span,
+            property,
+            words,
+        ));
+    }
+
+    let span = group.span();
+    Ok(syn::parse_quote_spanned!(span => [#(#elems),*]))
+}
+
+/// Checks whether the input is at the end of the current value, i.e. at
+/// the end of the block or at a comma followed by the next entry or rule.
+/// Other commas are part of the value, e.g. in `font-family: Arial, sans-serif`
+/// or `transition: opacity {d}ms, transform {d}ms`.
+fn ends_value(input: ParseStream) -> bool {
+    if input.is_empty() {
+        return true;
+    }
+    if !input.peek(Token![,]) {
+        return false;
+    }
+
+    let fork = input.fork();
+    let _ = fork.parse::<Token![,]>();
+    if fork.is_empty() || Control::<()>::peek(&fork) {
+        return true;
+    }
+    if !fork.peek(syn::Ident) && !fork.peek(syn::LitStr) {
+        return false;
+    }
+    if fork.peek2(Token![:]) {
+        return true;
+    }
+
+    if let Ok(lit) = fork.parse::<syn::LitStr>() {
+        // Statements like `"@import ..."`
+        if lit.value().trim_start().starts_with('@') {
+            return true;
+        }
+    } else {
+        let _ = fork.parse::<syn::Ident>();
+    }
+
+    // A nested rule, unless the braces contain an interpolated expression
+    match fork.parse::<TokenTree>() {
+        Ok(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            !is_interpolation(group.stream())
+        }
+        _ => false,
+    }
+}
+
+/// Checks whether the content of braces is a Rust expression, as opposed to
+/// the body of a rule
+fn is_interpolation(tokens: TokenStream) -> bool {
+    !tokens.is_empty() && syn::parse2::<syn::Expr>(tokens).is_ok()
+}
+
+/// Returns the identifier if the expression is a single one, possibly
+/// negated, e.g. `flex` or `-gap`
+fn single_ident(expr: &syn::Expr) -> Option<&syn::Ident> {
+    match expr {
+        syn::Expr::Path(path) if path.qself.is_none() => path.path.get_ident(),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => single_ident(expr),
+        _ => None,
+    }
+}
+
+/// Checks whether a valid Rust expression is meant to be css, e.g. `flex`
+/// (a single identifier), `5px` (a literal with a unit), `space-between`
+/// (a hyphenated identifier), `16 / 9` (a ratio) or `rotate(45deg)` (a css
+/// function). Identifiers within these are added to `idents`.
+fn is_css(expr: &syn::Expr, idents: &mut Vec<syn::Ident>) -> bool {
+    match expr {
+        syn::Expr::Path(path) if path.qself.is_none() => match path.path.get_ident() {
+            Some(ident) => {
+                idents.push(ident.clone());
+                true
+            }
+            None => false,
+        },
+        syn::Expr::Lit(syn::ExprLit { lit, .. }) => match lit {
+            syn::Lit::Int(lit) => !RUST_SUFFIXES.contains(&lit.suffix()),
+            syn::Lit::Float(lit) => !RUST_SUFFIXES.contains(&lit.suffix()),
+            _ => false,
+        },
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => is_css(expr, idents),
+        syn::Expr::Binary(syn::ExprBinary {
+            op: syn::BinOp::Sub(_),
+            left,
+            right,
+            ..
+        }) => is_css(left, idents) && is_css(right, idents),
+        // Ratios like `16 / 9` or `1 / 3` are css, not integer divisions
+        syn::Expr::Binary(syn::ExprBinary {
+            op: syn::BinOp::Div(_),
+            left,
+            right,
+            ..
+        }) => {
+            let mut is_number = |expr: &syn::Expr| {
+                is_css(expr, idents)
+                    || matches!(
+                        expr,
+                        syn::Expr::Lit(syn::ExprLit {
+                            lit: syn::Lit::Int(_) | syn::Lit::Float(_),
+                            ..
+                        })
+                    )
+            };
+            is_number(left) && is_number(right)
+        }
+        // `solid {color}` is a word followed by an interpolation; Rust structs
+        // are not lowercase
+        syn::Expr::Struct(expr) if expr.qself.is_none() => match expr.path.get_ident() {
+            Some(ident)
+                if ident
+                    .to_string()
+                    .starts_with(|ch: char| ch.is_ascii_lowercase()) =>
+            {
+                idents.push(ident.clone());
+                true
+            }
+            _ => false,
+        },
+        syn::Expr::Call(call) => {
+            let syn::Expr::Path(func) = &*call.func else {
+                return false;
+            };
+            let Some(name) = func.path.get_ident() else {
+                return false;
+            };
+            let mut css_args = false;
+            for arg in &call.args {
+                css_args |= is_css(arg, idents);
+            }
+            FUNCTIONS.iter().any(|function| name == function) || css_args
+        }
+        _ => false,
+    }
+}
+
+/// The previous token, which determines the whitespace in front of the next one
+#[derive(Clone, Copy, PartialEq)]
+enum Prev {
+    /// Nothing, or something the next token is attached to, e.g. `#`
+    Attached,
+    Ident,
+    /// A number, string, `%` or group
+    Value,
+    /// A spliced expression. Units are attached to it, e.g. in `{d}ms`
+    Expr,
+    Comma,
+}
+
+#[derive(Default)]
+struct Css {
+    format_str: String,
+    text: String,
+    args: Vec<TokenStream>,
+}
+
+impl Css {
+    fn push(&mut self, s: &str) {
+        self.format_str
+            .push_str(&s.replace('{', "{{").replace('}', "}}"));
+        self.text.push_str(s);
+    }
+
+    fn push_tokens(&mut self, tokens: TokenStream, math: bool) {
+        let mut prev = Prev::Attached;
+        let mut last_ident = String::new();
+
+        for token in tokens {
+            let space = prev != Prev::Attached;
+            match token {
+                TokenTree::Ident(ident) => {
+                    let unit = prev == Prev::Expr && values::UNITS.contains(&&*ident.to_string());
+                    if space && !unit {
+                        self.push(" ");
+                    }
+                    last_ident = ident.to_string();
+                    self.push(&last_ident);
+                    prev = Prev::Ident;
+                    continue;
+                }
+                TokenTree::Literal(lit) => {
+                    if space {
+                        self.push(" ");
+                    }
+                    self.push(&lit.to_string());
+                    prev = Prev::Value;
+                }
+                TokenTree::Group(group) => match group.delimiter() {
+                    Delimiter::Brace => {
+                        if space {
+                            self.push(" ");
+                        }
+                        self.format_str.push_str("{}");
+                        self.args.push(group.stream());
+                        prev = Prev::Expr;
+                    }
+                    Delimiter::Parenthesis => {
+                        // Function calls like `rgba(...)` are attached to their name
+                        let call = prev == Prev::Ident;
+                        if space && !call {
+                            self.push(" ");
+                        }
+                        let math = match call {
+                            true => MATH_FUNCTIONS.contains(&last_ident.as_str()),
+                            false => math,
+                        };
+                        self.push("(");
+                        self.push_tokens(group.stream(), math);
+                        self.push(")");
+                        prev = Prev::Value;
+                    }
+                    Delimiter::Bracket => {
+                        if space {
+                            self.push(" ");
+                        }
+                        self.push("[");
+                        self.push_tokens(group.stream(), math);
+                        self.push("]");
+                        prev = Prev::Value;
+                    }
+                    Delimiter::None => {
+                        self.push_tokens(group.stream(), math);
+                        prev = Prev::Value;
+                    }
+                },
+                TokenTree::Punct(punct) => {
+                    let ch = punct.as_char();
+                    match ch {
+                        '%' => {
+                            self.push("%");
+                            prev = Prev::Value;
+                        }
+                        ',' => {
+                            self.push(",");
+                            prev = Prev::Comma;
+                        }
+                        '-' | '+' if math && matches!(prev, Prev::Value | Prev::Expr) => {
+                            self.push(&format!(" {ch} "));
+                            prev = Prev::Attached;
+                        }
+                        '-' if prev == Prev::Ident => {
+                            // Hyphenated identifiers like `space-between`
+                            self.push("-");
+                            prev = Prev::Attached;
+                        }
+                        '*' | '/' => {
+                            self.push(&format!(" {ch} "));
+                            prev = Prev::Attached;
+                        }
+                        ch => {
+                            // Prefixes like the `#` in `#fff` or the `-` in `-1px`
+                            if space {
+                                self.push(" ");
+                            }
+                            self.push(&ch.to_string());
+                            prev = Prev::Attached;
+                        }
+                    }
+                }
+            }
+            last_ident.clear();
+        }
+    }
+
+    fn into_expr(self, span: Span) -> syn::Expr {
+        if self.args.is_empty() {
+            let lit = syn::LitStr::new(&self.text, span);
+            return syn::parse_quote_spanned!(span => #lit);
+        }
+
+        let format_str = syn::LitStr::new(&self.format_str, span);
+        let args = self.args;
+        let tokens = quote_spanned!(span => ::core::format_args!(#format_str #(, #args)*));
+        syn::parse2(tokens).expect("format_args! is a valid expression")
+    }
+}
@@ -0,0 +1,91 @@
+//! Default units for numeric values, e.g. `margin: 8` becomes `8px`.
+//! Lengths (see [values::LENGTH_PROPERTIES]) get `px`, durations `ms`. Other
+//! properties (like `opacity`, `z-index`, `flex-grow` or `line-height`) take
+//! plain numbers, which are written as they are.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+
+use super::values;
+
+/// Properties taking durations, sorted alphabetically
+const MS: &[&str] = &[
+    "animation-delay",
+    "animation-duration",
+    "transition-delay",
+    "transition-duration",
+];
+
+/// Returns the unit of plain numbers given for the property, if any
+pub fn default_unit(property: &str) -> Option<&'static str> {
+    if values::LENGTH_PROPERTIES.binary_search(&property).is_ok() {
+        Some("px")
+    } else if MS.binary_search(&property).is_ok() {
+        Some("ms")
+    } else {
+        None
+    }
+}
+
+/// Appends the default unit of the property to a numeric value. Number
+/// literals are converted at compile time. Other expressions are checked at
+/// runtime: numbers get the unit, anything else (e.g. `Px(8.0)` or a
+/// string) is used as it is.
+pub fn apply(property: &str, value: &syn::Expr) -> TokenStream {
+    let Some(unit) = default_unit(property) else {
+        return quote!(#value);
+    };
+
+    if let Some(number) = number_literal(value) {
+        let lit = syn::LitStr::new(&format!("{number}{unit}"), value.span());
+        return quote!(#lit);
+    }
+
+    match value {
+        // String literals and css values given as `format_args!`
+        syn::Expr::Lit(_) | syn::Expr::Macro(_) => quote!(#value),
+        value => {
+            let with_unit = apply_to_ref(property, value.span());
+            quote_spanned! {value.span()=>
+                match &(#value) {
+                    value => #with_unit
+                }
+            }
+        }
+    }
+}
+
+/// Like [apply], but for a reference to a value given as Rust expression,
+/// bound to `value`
+pub fn apply_to_ref(property: &str, span: Span) -> TokenStream {
+    let Some(unit) = default_unit(property) else {
+        return quote_spanned!(span=> value);
+    };
+
+    quote_spanned! {span=>
+        {
+            // Only one of the traits is used, depending on the type
+            #[allow(unused_imports)]
+            use ::css_in_rs::values::default_unit::{Number as _, Other as _};
+            (&::css_in_rs::values::default_unit::Value(value, #unit)).with_default_unit()
+        }
+    }
+}
+
+/// Returns the text of number literals like `8`, `1.5` or `-2`
+pub fn number_literal(value: &syn::Expr) -> Option<String> {
+    match value {
+        syn::Expr::Lit(syn::ExprLit { lit, .. }) => match lit {
+            syn::Lit::Int(lit) => Some(lit.base10_digits().to_string()),
+            syn::Lit::Float(lit) => Some(lit.base10_digits().to_string()),
+            _ => None,
+        },
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => number_literal(expr).map(|number| format!("-{number}")),
+        _ => None,
+    }
+}
@@ -0,0 +1,379 @@
+//! Checks for css values given as string literals. The checks are not
+//! complete; they are supposed to catch obvious typos like `display: "flexx"`
+//! or `color: "#ggg"`. Everything which cannot be checked is accepted.
+
+use super::{keywords, suggest};
+
+/// Properties which take exactly one color
+const COLOR_PROPERTIES: &[&str] = &[
+    "accent-color",
+    "background-color",
+    "border-block-end-color",
+    "border-block-start-color",
+    "border-bottom-color",
+    "border-inline-end-color",
+    "border-inline-start-color",
+    "border-left-color",
+    "border-right-color",
+    "border-top-color",
+    "caret-color",
+    "color",
+    "column-rule-color",
+    "outline-color",
+    "text-decoration-color",
+];
+
+/// Properties taking lengths, sorted alphabetically. They do not accept
+/// unitless numbers (except for zero); numbers given as Rust expressions
+/// get the default unit `px`. Shorthands like `border` are missing on
+/// purpose; they also take styles and colors.
+pub const LENGTH_PROPERTIES: &[&str] = &[
+    "background-size",
+    "block-size",
+    "border-block-end-width",
+    "border-block-start-width",
+    "border-block-width",
+    "border-bottom-left-radius",
+    "border-bottom-right-radius",
+    "border-bottom-width",
+    "border-end-end-radius",
+    "border-end-start-radius",
+    "border-inline-end-width",
+    "border-inline-start-width",
+    "border-inline-width",
+    "border-left-width",
+    "border-radius",
+    "border-right-width",
+    "border-spacing",
+    "border-start-end-radius",
+    "border-start-start-radius",
+    "border-top-left-radius",
+    "border-top-right-radius",
+    "border-top-width",
+    "border-width",
+    "bottom",
+    "column-gap",
+    "column-rule-width",
+    "column-width",
+    "flex-basis",
+    "font-size",
+    "gap",
+    "height",
+    "inline-size",
+    "inset",
+    "inset-block",
+    "inset-block-end",
+    "inset-block-start",
+    "inset-inline",
+    "inset-inline-end",
+    "inset-inline-start",
+    "left",
+    "letter-spacing",
+    "margin",
+    "margin-block",
+    "margin-block-end",
+    "margin-block-start",
+    "margin-bottom",
+    "margin-inline",
+    "margin-inline-end",
+    "margin-inline-start",
+    "margin-left",
+    "margin-right",
+    "margin-top",
+    "max-block-size",
+    "max-height",
+    "max-inline-size",
+    "max-width",
+    "min-block-size",
+    "min-height",
+    "min-inline-size",
+    "min-width",
+    "outline-offset",
+    "outline-width",
+    "padding",
+    "padding-block",
+    "padding-block-end",
+    "padding-block-start",
+    "padding-bottom",
+    "padding-inline",
+    "padding-inline-end",
+    "padding-inline-start",
+    "padding-left",
+    "padding-right",
+    "padding-top",
+    "perspective",
+    "right",
+    "row-gap",
+    "scroll-margin",
+    "scroll-margin-bottom",
+    "scroll-margin-left",
+    "scroll-margin-right",
+    "scroll-margin-top",
+    "scroll-padding",
+    "scroll-padding-bottom",
+    "scroll-padding-left",
+    "scroll-padding-right",
+    "scroll-padding-top",
+    "text-decoration-thickness",
+    "text-indent",
+    "text-underline-offset",
+    "top",
+    "width",
+    "word-spacing",
+];
+
+pub const UNITS: &[&str] = &[
+    "%", "cap", "ch", "cm", "cqb", "cqh", "cqi", "cqmax", "cqmin", "cqw", "deg", "dpcm", "dpi",
+    "dppx", "dvb", "dvh", "dvi", "dvmax", "dvmin", "dvw", "em", "ex", "fr", "grad", "hz", "ic",
+    "in", "khz", "lh", "lvb", "lvh", "lvi", "lvmax", "lvmin", "lvw", "mm", "ms", "pc", "pt", "px",
+    "q", "rad", "rcap", "rch", "rem", "rex", "ric", "rlh", "s", "svb", "svh", "svi", "svmax",
+    "svmin", "svw", "turn", "vb", "vh", "vi", "vmax", "vmin", "vw", "x",
+];
+
+const COLOR_FUNCTIONS: &[&str] = &[
+    "color",
+    "color-mix",
+    "hsl",
+    "hsla",
+    "hwb",
+    "lab",
+    "lch",
+    "light-dark",
+    "oklab",
+    "oklch",
+    "rgb",
+    "rgba",
+];
+
+const NAMED_COLORS: &str = "aliceblue antiquewhite aqua aquamarine azure beige bisque black \
+    blanchedalmond blue blueviolet brown burlywood cadetblue chartreuse chocolate coral \
+    cornflowerblue cornsilk crimson cyan darkblue darkcyan darkgoldenrod darkgray darkgreen \
+    darkgrey darkkhaki darkmagenta darkolivegreen darkorange darkorchid darkred darksalmon \
+    darkseagreen darkslateblue darkslategray darkslategrey darkturquoise darkviolet deeppink \
+    deepskyblue dimgray dimgrey dodgerblue firebrick floralwhite forestgreen fuchsia gainsboro \
+    ghostwhite gold goldenrod gray green greenyellow grey honeydew hotpink indianred indigo \
+    ivory khaki lavender lavenderblush lawngreen lemonchiffon lightblue lightcoral lightcyan \
+    lightgoldenrodyellow lightgray lightgreen lightgrey lightpink lightsalmon lightseagreen \
+    lightskyblue lightslategray lightslategrey lightsteelblue lightyellow lime limegreen linen \
+    magenta maroon mediumaquamarine mediumblue mediumorchid mediumpurple mediumseagreen \
+    mediumslateblue mediumspringgreen mediumturquoise mediumvioletred midnightblue mintcream \
+    mistyrose moccasin navajowhite navy oldlace olive olivedrab orange orangered orchid \
+    palegoldenrod palegreen paleturquoise palevioletred papayawhip peachpuff peru pink plum \
+    powderblue purple rebeccapurple red rosybrown royalblue saddlebrown salmon sandybrown \
+    seagreen seashell sienna silver skyblue slateblue slategray slategrey snow springgreen \
+    steelblue tan teal thistle tomato turquoise violet wheat white whitesmoke yellow \
+    yellowgreen transparent currentcolor";
+
+/// Keywords of properties in [LENGTH_PROPERTIES]
+const LENGTH_KEYWORDS: &[&str] = &[
+    "auto",
+    "contain",
+    "cover",
+    "fit-content",
+    "large",
+    "larger",
+    "max-content",
+    "medium",
+    "min-content",
+    "none",
+    "normal",
+    "small",
+    "smaller",
+    "thick",
+    "thin",
+];
+
+/// Checks whether a single word is a keyword of the given property: a global
+/// keyword, a keyword of a property which only takes keywords, a named color
+/// of a color property, or a keyword like `auto` of a length property. This
+/// tells css like `display: flex` apart from Rust variables like `width: w`.
+pub fn is_keyword(property: Option<&str>, word: &str) -> bool {
+    let word = word.to_ascii_lowercase();
+    if keywords::GLOBAL.contains(&word.as_str()) {
+        return true;
+    }
+    let Some(property) = property else {
+        return false;
+    };
+
+    if let Some(mut keywords) = keywords::get(property) {
+        return keywords.any(|keyword| keyword == word);
+    }
+    if COLOR_PROPERTIES.contains(&property) {
+        return NAMED_COLORS.split_whitespace().any(|color| color == word);
+    }
+    LENGTH_PROPERTIES.contains(&property) && LENGTH_KEYWORDS.contains(&word.as_str())
+}
+
+/// Checks the value of the given property. Returns a descriptive error
+/// message if the value is definitely invalid.
+pub fn check(property: &str, value: &str) -> Result<(), String> {
+    let value = strip_important(value).0.trim();
+    let lowercase = value.to_ascii_lowercase();
+
+    if keywords::GLOBAL.contains(&lowercase.as_str()) {
+        return Ok(());
+    }
+
+    // Functions like `var()` may expand to anything; strings are opaque
+    if value.contains(['(', '"', '\'']) && !COLOR_PROPERTIES.contains(&property) {
+        return Ok(());
+    }
+
+    if value.is_empty() {
+        return Err(format!("Empty value for css property `{property}`"));
+    }
+
+    if let Some(keywords) = keywords::get(property) {
+        return check_keywords(property, &lowercase, keywords);
+    }
+
+    if COLOR_PROPERTIES.contains(&property) {
+        return check_color(&lowercase);
+    }
+
+    check_numbers(property, &lowercase)
+}
+
+/// Splits a trailing `!important` off a css value, e.g. `red !important`.
+/// Returns the value in front of it, and whether it was there.
+pub fn strip_important(value: &str) -> (&str, bool) {
+    let trimmed = value.trim_end();
+    let Some(bang) = trimmed.rfind('!') else {
+        return (value, false);
+    };
+    match trimmed[bang + 1..]
+        .trim_start()
+        .eq_ignore_ascii_case("important")
+    {
+        true => (value[..bang].trim_end(), true),
+        false => (value, false),
+    }
+}
+
+fn check_keywords(
+    property: &str,
+    value: &str,
+    keywords: impl Iterator<Item = &'static str> + Clone,
+) -> Result<(), String> {
+    for word in value.split_whitespace() {
+        if keywords.clone().any(|keyword| keyword == word) || keywords::MODIFIERS.contains(&word) {
+            continue;
+        }
+        // Vendor prefixed keywords like `-webkit-sticky`
+        if word.starts_with('-') {
+            continue;
+        }
+
+        let mut msg = format!("Invalid value `{word}` for css property `{property}`");
+        if let Some(suggestion) = suggest::closest(word, keywords) {
+            msg.push_str(&format!("; did you mean `{suggestion}`?"));
+        }
+        return Err(msg);
+    }
+
+    Ok(())
+}
+
+fn check_color(value: &str) -> Result<(), String> {
+    if let Some(hex) = value.strip_prefix('#') {
+        let valid =
+            matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|ch| ch.is_ascii_hexdigit());
+        return match valid {
+            true => Ok(()),
+            false => Err(format!(
+                "Invalid hex color `{value}`; expected 3, 4, 6 or 8 hexadecimal digits"
+            )),
+        };
+    }
+
+    if let Some((function, _)) = value.split_once('(') {
+        let function = function.trim();
+        if function == "var" || function == "env" || COLOR_FUNCTIONS.contains(&function) {
+            return match value.ends_with(')') {
+                true => Ok(()),
+                false => Err(format!("Missing `)` in color `{value}`")),
+            };
+        }
+
+        let mut msg = format!("Unknown color function `{function}`");
+        if let Some(suggestion) = suggest::closest(function, COLOR_FUNCTIONS.iter().copied()) {
+            msg.push_str(&format!("; did you mean `{suggestion}`?"));
+        }
+        return Err(msg);
+    }
+
+    // Vendor prefixed keywords like `-webkit-link`
+    if NAMED_COLORS.split_whitespace().any(|color| color == value) || value.starts_with('-') {
+        return Ok(());
+    }
+
+    let mut msg = format!("Invalid color `{value}`");
+    if let Some(suggestion) = suggest::closest(value, NAMED_COLORS.split_whitespace()) {
+        msg.push_str(&format!("; did you mean `{suggestion}`?"));
+    }
+    Err(msg)
+}
+
+/// Checks the units of all numbers in the value
+fn check_numbers(property: &str, value: &str) -> Result<(), String> {
+    let is_separator = |ch: char| ch.is_whitespace() || matches!(ch, ',' | '/' | '*');
+
+    for word in value.split(is_separator) {
+        let number_len = number_len(word);
+        if number_len == 0 {
+            continue;
+        }
+
+        let (number, unit) = word.split_at(number_len);
+        if unit.is_empty() {
+            let is_zero = number.chars().all(|ch| matches!(ch, '0' | '.' | '+' | '-'));
+            if !is_zero && LENGTH_PROPERTIES.contains(&property) {
+                return Err(format!(
+                    "Missing unit in `{word}` for css property `{property}`, e.g. `{word}px`"
+                ));
+            }
+            continue;
+        }
+
+        if !UNITS.contains(&unit) {
+            let mut msg = format!("Unknown unit `{unit}` in `{word}`");
+            if let Some(suggestion) = suggest::closest(unit, UNITS.iter().copied()) {
+                msg.push_str(&format!("; did you mean `{number}{suggestion}`?"));
+            }
+            return Err(msg);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the length of the number at the beginning of `word`, or zero
+/// if `word` does not start with a number
+fn number_len(word: &str) -> usize {
+    let bytes = word.as_bytes();
+    let mut idx = 0;
+
+    if matches!(bytes.first(), Some(b'+' | b'-')) {
+        idx += 1;
+    }
+
+    let digits_start = idx;
+    while idx < bytes.len() && (bytes[idx].is_ascii_digit() || bytes[idx] == b'.') {
+        idx += 1;
+    }
+
+    if !bytes[digits_start..idx].iter().any(u8::is_ascii_digit) {
+        return 0;
+    }
+
+    // Exponent, e.g. `1e3`
+    if idx + 1 < bytes.len() && bytes[idx] == b'e' && bytes[idx + 1].is_ascii_digit() {
+        idx += 1;
+        while idx < bytes.len() && bytes[idx].is_ascii_digit() {
+            idx += 1;
+        }
+    }
+
+    idx
+}
@@ -1,23 +1,34 @@
-use std::collections::{btree_map::Entry, BTreeMap};
+use std::collections::{btree_map::Entry, BTreeMap, BTreeSet};
 
-use proc_macro2::Span;
-use syn::parse::{Parse, ParseStream};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    Token,
+};
 
 use crate::output::{Output, ToOutput};
 
-#[derive(Debug)]
+mod error;
+mod parse;
+
+#[derive(Clone, Debug)]
 pub enum Part {
     Raw(String),
     ClassName(String),
+    /// The name of a `@keyframes` rule. It is scoped just like classnames
+    Keyframes(String),
+    /// A Rust expression interpolated via `{expr}`
+    Expr(TokenStream),
 }
 
-impl Part {
-    fn is_class(&self) -> bool {
-        matches!(self, Part::ClassName(_))
-    }
+/// Returns the name of the struct member for the given classname. Classnames
+/// may be kebab-case, but struct members are snake_case.
+pub fn field_name(classname: &str) -> String {
+    classname.replace('-', "_")
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Header {
     pub parts: Vec<Part>,
     pub span: Span,
@@ -28,20 +39,77 @@ impl Header {
     pub fn collect_classnames(&self, result: &mut BTreeMap<String, Span>) {
         for part in &self.parts {
             match part {
-                Part::Raw(_) => {}
-                Part::ClassName(classname) => {
+                Part::Raw(_) | Part::Expr(_) => {}
+                Part::ClassName(classname) | Part::Keyframes(classname) => {
                     let classname = classname.to_string();
-                    match result.entry(classname) {
-                        Entry::Vacant(vac) => {
-                            vac.insert(self.span.clone());
-                        }
-                        _ => {}
+                    if let Entry::Vacant(vac) = result.entry(classname) {
+                        vac.insert(self.span);
                     }
                 }
             }
         }
     }
 
+    pub fn collect_keyframes(&self, result: &mut BTreeSet<String>) {
+        for part in &self.parts {
+            if let Part::Keyframes(name) = part {
+                result.insert(name.to_string());
+            }
+        }
+    }
+
+    /// Checks whether this is the header of a `@global { ... }` block
+    pub fn is_global(&self) -> bool {
+        matches!(self.parts.as_slice(), [Part::Raw(s)] if s == "@global")
+    }
+
+    /// Returns the name of the at-rule without `@`, e.g. `media` for
+    /// `@media screen`. Returns `None` for style rules.
+    pub fn at_rule_name(&self) -> Option<&str> {
+        match self.parts.first() {
+            Some(Part::Raw(s)) if self.at_rule => {
+                let name = s.strip_prefix('@')?;
+                let end = name
+                    .find(|ch: char| !ch.is_alphanumeric() && ch != '-' && ch != '_')
+                    .unwrap_or(name.len());
+                Some(&name[..end])
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks whether an identifier header like `item:hover` follows. Within
+    /// style rules, `border: solid {color}` is a declaration instead, so each
+    /// pseudo-class must be a known one, directly following its colon.
+    pub fn peek_ident(input: ParseStream) -> bool {
+        let fork = input.fork();
+        if fork.parse::<syn::Ident>().is_err() {
+            return false;
+        }
+
+        while fork.peek(Token![:]) {
+            if !pseudo::attached(&fork) {
+                return false;
+            }
+            match pseudo::parse(&fork) {
+                Ok(pseudo) if pseudo::is_known(&pseudo) => {}
+                _ => return false,
+            }
+        }
+        fork.peek(syn::token::Brace)
+    }
+
+    /// Turns all classnames into plain text, i.e. they won't be scoped
+    pub fn unscope(&mut self) {
+        let parts = std::mem::take(&mut self.parts);
+        for part in parts {
+            match part {
+                Part::ClassName(s) | Part::Keyframes(s) => self.push_part(Part::Raw(s)),
+                part => self.push_part(part),
+            }
+        }
+    }
+
     pub fn push_char(&mut self, ch: char) {
         if let Some(Part::Raw(r)) = self.parts.last_mut() {
             r.push(ch);
@@ -50,68 +118,289 @@ impl Header {
             self.parts.push(part);
         }
     }
-}
 
-mod parse {
-    use proc_macro2::Span;
+    fn push_part(&mut self, part: Part) {
+        match part {
+            Part::Raw(s) => s.chars().for_each(|ch| self.push_char(ch)),
+            part => self.parts.push(part),
+        }
+    }
 
-    use super::{Header, Part};
+    /// Returns the selector of a rule nested in a rule with the
+    /// selector `parent`. Each `&` is replaced by the parent selector.
+    /// If there is no `&`, the rule is treated as a descendant of its
+    /// parent (like in SCSS). Selector lists are combined pairwise,
+    /// i.e. `a, b` and `&:hover, &:focus` yields four selectors.
+    pub fn resolve(&self, parent: &Header) -> Header {
+        let mut header = Header {
+            parts: Default::default(),
+            span: self.span,
+            at_rule: false,
+        };
 
-    type ParseResult<'a, T> = nom::IResult<&'a str, T>;
+        let parents = split_selectors(&parent.parts);
+        let selectors = split_selectors(&self.parts);
 
-    pub fn parse(src: &str, span: Span) -> Option<Header> {
-        let mut src = src.trim();
-        let at_rule = src.starts_with('@');
+        for parent in &parents {
+            for selector in &selectors {
+                if !header.parts.is_empty() {
+                    header.push_part(Part::Raw(", ".to_owned()));
+                }
 
-        let mut header = Header {
-            parts: Default::default(),
-            span,
-            at_rule,
+                let has_ampersand = selector
+                    .iter()
+                    .any(|part| matches!(part, Part::Raw(s) if s.contains('&')));
+
+                if !has_ampersand {
+                    parent.iter().for_each(|p| header.push_part(p.clone()));
+                    header.push_char(' ');
+                    selector.iter().for_each(|p| header.push_part(p.clone()));
+                    continue;
+                }
+
+                for part in selector {
+                    let Part::Raw(s) = part else {
+                        header.push_part(part.clone());
+                        continue;
+                    };
+
+                    for ch in s.chars() {
+                        if ch == '&' {
+                            parent.iter().for_each(|p| header.push_part(p.clone()));
+                        } else {
+                            header.push_char(ch);
+                        }
+                    }
+                }
+            }
+        }
+
+        header
+    }
+}
+
+/// Splits a selector list at top level commas (i.e. not within
+/// parentheses or strings). Surrounding whitespace of each selector is removed.
+fn split_selectors(parts: &[Part]) -> Vec<Vec<Part>> {
+    let mut result = vec![Vec::new()];
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+
+    for part in parts {
+        let s = match part {
+            Part::Raw(s) => s,
+            part => {
+                result.last_mut().unwrap().push(part.clone());
+                continue;
+            }
         };
 
-        loop {
-            let (remaining, part) = parse_part(src, !at_rule).ok()?;
-            if part.is_class() {
-                header.push_char('.');
+        let mut chunk = String::new();
+        for ch in s.chars() {
+            if escaped || quote.is_some() {
+                match ch {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    ch if Some(ch) == quote => quote = None,
+                    _ => {}
+                }
+                chunk.push(ch);
+                continue;
             }
-            header.parts.push(part);
 
-            src = remaining;
-            if src.is_empty() {
-                break;
+            match ch {
+                '\\' => escaped = true,
+                '"' | '\'' => quote = Some(ch),
+                '(' | '[' => depth += 1,
+                ')' | ']' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    let current = result.last_mut().unwrap();
+                    current.push(Part::Raw(std::mem::take(&mut chunk)));
+                    result.push(Vec::new());
+                    continue;
+                }
+                _ => {}
             }
+            chunk.push(ch);
         }
+        result.last_mut().unwrap().push(Part::Raw(chunk));
+    }
 
-        Some(header)
+    for selector in &mut result {
+        selector.retain(|part| !matches!(part, Part::Raw(s) if s.is_empty()));
+        if let Some(Part::Raw(s)) = selector.first_mut() {
+            *s = s.trim_start().to_owned();
+        }
+        if let Some(Part::Raw(s)) = selector.last_mut() {
+            *s = s.trim_end().to_owned();
+        }
     }
 
-    fn parse_classname(src: &str) -> ParseResult<&str> {
-        let (src, _) = nom::bytes::complete::tag(".")(src)?;
-        let (src, classname) =
-            nom::bytes::complete::take_while1(|ch: char| ch.is_ascii_alphanumeric() || ch == '_')(
-                src,
-            )?;
+    result
+}
+
+/// Parses pseudo-classes and pseudo-elements directly from tokens,
+/// e.g. the `:hover` in `item:hover { ... }`
+mod pseudo {
+    use proc_macro2::{Delimiter, Span, TokenStream, TokenTree};
+    use syn::{ext::IdentExt, parse::ParseStream, Token};
+
+    /// Pseudo-classes and pseudo-elements which may be written with a single
+    /// colon. Functional ones like `not` take their arguments in parentheses.
+    const KNOWN: &[&str] = &[
+        "active",
+        "after",
+        "any-link",
+        "autofill",
+        "before",
+        "blank",
+        "checked",
+        "default",
+        "defined",
+        "dir",
+        "disabled",
+        "empty",
+        "enabled",
+        "first-child",
+        "first-letter",
+        "first-line",
+        "first-of-type",
+        "focus",
+        "focus-visible",
+        "focus-within",
+        "fullscreen",
+        "has",
+        "host",
+        "hover",
+        "in-range",
+        "indeterminate",
+        "invalid",
+        "is",
+        "lang",
+        "last-child",
+        "last-of-type",
+        "link",
+        "modal",
+        "not",
+        "nth-child",
+        "nth-last-child",
+        "nth-last-of-type",
+        "nth-of-type",
+        "only-child",
+        "only-of-type",
+        "open",
+        "optional",
+        "out-of-range",
+        "placeholder-shown",
+        "popover-open",
+        "read-only",
+        "read-write",
+        "required",
+        "root",
+        "scope",
+        "target",
+        "user-invalid",
+        "user-valid",
+        "valid",
+        "visited",
+        "where",
+    ];
 
-        if src.starts_with('-') {
-            // '-' is not allowed in identifiers
-            let err = nom::error::make_error(src, nom::error::ErrorKind::Fail);
-            return Err(nom::Err::Failure(err));
+    /// Checks whether a pseudo-class as returned by [`parse`] is a known one.
+    /// Pseudo-elements (`::before`) and vendor prefixed names are accepted.
+    pub fn is_known(pseudo: &str) -> bool {
+        if pseudo.starts_with("::") {
+            return true;
         }
+        let name = pseudo.trim_start_matches(':');
+        let name = name.split('(').next().unwrap_or_default();
+        name.starts_with('-') || KNOWN.contains(&name)
+    }
 
-        Ok((src, classname))
+    /// Checks whether the name follows the colon without whitespace, as in
+    /// `item:hover` but not in `border: solid`. Without span locations,
+    /// this cannot be told apart.
+    pub fn attached(input: ParseStream) -> bool {
+        let fork = input.fork();
+        let colon = match fork.peek(Token![::]) {
+            true => fork.parse::<Token![::]>().map(|colon| colon.spans[1]),
+            false => fork.parse::<Token![:]>().map(|colon| colon.span),
+        };
+        let Ok(colon) = colon else {
+            return false;
+        };
+        let name = fork.span();
+        let unlocated = |span: Span| span.start() == span.end();
+        unlocated(colon) || unlocated(name) || colon.end() == name.start()
     }
 
-    fn parse_part(src: &str, handle_dots: bool) -> ParseResult<Part> {
-        if handle_dots && src.starts_with('.') {
-            let (src, classname) = parse_classname(src)?;
-            let part = Part::ClassName(classname.to_string());
-            return Ok((src, part));
+    pub fn parse(input: ParseStream) -> syn::Result<String> {
+        let mut result = String::new();
+        if input.peek(Token![::]) {
+            input.parse::<Token![::]>()?;
+            result.push_str("::");
+        } else {
+            input.parse::<Token![:]>()?;
+            result.push(':');
         }
 
-        let (src, chunk) =
-            nom::bytes::complete::take_while1(|ch: char| !handle_dots || ch != '.')(src)?;
-        let part = Part::Raw(chunk.to_string());
-        Ok((src, part))
+        // The name may contain hyphens, e.g. `first-child` or `-webkit-scrollbar`
+        loop {
+            if input.peek(Token![-]) {
+                input.parse::<Token![-]>()?;
+                result.push('-');
+            }
+
+            let name = input.call(syn::Ident::parse_any).map_err(|err| {
+                syn::Error::new(err.span(), "Expected a pseudo-class or pseudo-element")
+            })?;
+            result.push_str(&name.to_string());
+
+            if !input.peek(Token![-]) {
+                break;
+            }
+        }
+
+        if input.peek(syn::token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+            result.push('(');
+            push_tokens(&mut result, content.parse()?);
+            result.push(')');
+        }
+
+        Ok(result)
+    }
+
+    /// Reconstructs the source of the arguments of functional pseudo-classes
+    /// like `:not(:disabled)` or `:nth-child(2n+1)`. Whitespace is only kept
+    /// where it is significant, i.e. between two words.
+    fn push_tokens(result: &mut String, tokens: TokenStream) {
+        let mut last_was_word = false;
+
+        for token in tokens {
+            let is_word = matches!(token, TokenTree::Ident(_) | TokenTree::Literal(_));
+            if is_word && last_was_word {
+                result.push(' ');
+            }
+            last_was_word = is_word;
+
+            match token {
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::Brace => ("{", "}"),
+                        Delimiter::None => ("", ""),
+                    };
+                    result.push_str(open);
+                    push_tokens(result, group.stream());
+                    result.push_str(close);
+                }
+                token => result.push_str(&token.to_string()),
+            }
+        }
     }
 }
 
@@ -124,22 +413,23 @@ impl Parse for Header {
             ];
             let span = ident.span();
             let at_rule = false;
-            Header {
+            let mut header = Header {
                 parts,
                 span,
                 at_rule,
+            };
+
+            while input.peek(Token![:]) {
+                let suffix = pseudo::parse(input)?;
+                suffix.chars().for_each(|ch| header.push_char(ch));
             }
+
+            header
         } else {
             let source = input.parse::<syn::LitStr>()?;
 
-            if let Some(sel) = parse::parse(&source.value(), source.span()) {
-                return Ok(sel);
-            } else {
-                return Err(syn::Error::new(
-                    source.span(),
-                    "Parsing failed. Not a valid selector",
-                ));
-            }
+            parse::parse(&source.value(), source.span())
+                .map_err(|err| err.into_syn_error(&source))?
         };
 
         Ok(header)
@@ -150,7 +440,11 @@ impl ToOutput for Part {
     fn append(&self, result: &mut Output) {
         match self {
             Part::Raw(s) => result.push_str(s),
-            Part::ClassName(s) => result.push_classname(s),
+            Part::ClassName(s) | Part::Keyframes(s) => result.push_classname(s),
+            Part::Expr(expr) => {
+                result.format_str.push_str("{}");
+                quote!(, #expr).to_tokens(&mut result.params);
+            }
         }
     }
 }
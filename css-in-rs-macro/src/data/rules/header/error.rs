@@ -0,0 +1,65 @@
+use std::ops::Range;
+
+/// An error in a selector given as string literal. The range refers to the
+/// offending characters within the value of the literal.
+#[derive(Debug)]
+pub struct SelectorError {
+    pub range: Range<usize>,
+    pub msg: String,
+    pub help: Option<String>,
+}
+
+impl SelectorError {
+    pub fn new(range: Range<usize>, msg: impl Into<String>) -> Self {
+        Self {
+            range,
+            msg: msg.into(),
+            help: None,
+        }
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Converts this error into a `syn::Error`. If possible, the error points
+    /// to the offending characters within the literal. Since this is not
+    /// supported on all compilers, the message additionally marks them.
+    pub fn into_syn_error(self, source: &syn::LitStr) -> syn::Error {
+        let value = source.value();
+        let span = subspan(source, &self.range).unwrap_or_else(|| source.span());
+
+        let offset = value[..self.range.start].chars().count();
+        let len = value[self.range.clone()].chars().count().max(1);
+        let mut msg = format!(
+            "{}\n  {}\n  {}{}",
+            self.msg,
+            value,
+            " ".repeat(offset),
+            "^".repeat(len)
+        );
+        if let Some(help) = self.help {
+            msg.push_str("\nhelp: ");
+            msg.push_str(&help);
+        }
+
+        syn::Error::new(span, msg)
+    }
+}
+
+/// Returns the span of the given range within the value of the literal.
+/// Only works if the value can be found verbatim in the literal's source,
+/// i.e. there are no escape sequences.
+fn subspan(source: &syn::LitStr, range: &Range<usize>) -> Option<proc_macro2::Span> {
+    let token = source.token();
+    let repr = token.to_string();
+
+    // Skip the prefix of raw strings (`r#"`) or the opening quote
+    let offset = repr.find('"')? + 1;
+    if repr.get(offset..offset + source.value().len())? != source.value() {
+        return None;
+    }
+
+    token.subspan(offset + range.start..offset + range.end)
+}
@@ -0,0 +1,353 @@
+//! A tokenizer for selectors and at-rule preludes given as string literals.
+//! Class selectors are only recognized in selector positions; strings,
+//! attribute selectors, numbers (e.g. `12.5%`), comments and escapes are
+//! kept intact. Class selectors within `:global(...)` are not scoped; only
+//! these may contain escapes or non-ascii characters.
+//! Rust expressions may be interpolated via `{expr}`; `{{` and `}}` are
+//! literal braces.
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+
+use super::{error::SelectorError, Header, Part};
+
+pub fn parse(source: &str, span: Span) -> Result<Header, SelectorError> {
+    let src = source.trim();
+    let offset = source.len() - source.trim_start().len();
+    let at_rule = src.starts_with('@');
+
+    if src.is_empty() {
+        return Err(SelectorError::new(0..source.len(), "Empty selector"));
+    }
+
+    let mut header = Header {
+        parts: Default::default(),
+        span,
+        at_rule,
+    };
+
+    if at_rule {
+        if let Some((prefix, name)) = parse_keyframes(src) {
+            header.parts.push(Part::Raw(prefix.to_owned()));
+            header.parts.push(Part::Keyframes(name.to_owned()));
+            return Ok(header);
+        }
+    }
+
+    let tokenizer = Tokenizer {
+        src,
+        pos: 0,
+        handle_classes: !at_rule,
+        header: &mut header,
+    };
+    tokenizer.run().map_err(|mut err| {
+        err.range = offset + err.range.start..offset + err.range.end;
+        err
+    })?;
+
+    Ok(header)
+}
+
+/// Splits `@keyframes name` into the prefix `@keyframes ` and the name.
+/// Vendor prefixed versions like `@-webkit-keyframes` are supported.
+fn parse_keyframes(src: &str) -> Option<(&str, &str)> {
+    let keyword = nom::sequence::tuple((
+        nom::bytes::complete::tag("@"),
+        nom::combinator::opt(nom::sequence::delimited(
+            nom::bytes::complete::tag("-"),
+            nom::character::complete::alpha1,
+            nom::bytes::complete::tag("-"),
+        )),
+        nom::bytes::complete::tag("keyframes"),
+        nom::character::complete::multispace1,
+    ));
+    let (name, prefix) = nom::combinator::recognize::<_, _, (), _>(keyword)(src).ok()?;
+
+    let valid = name.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_' || ch == '-')
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-');
+    valid.then_some((prefix, name))
+}
+
+fn is_name_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' || !ch.is_ascii()
+}
+
+struct Tokenizer<'a> {
+    src: &'a str,
+    pos: usize,
+    handle_classes: bool,
+    header: &'a mut Header,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    /// Consumes the next char and appends it to the header verbatim
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += ch.len_utf8();
+        self.header.push_char(ch);
+        Some(ch)
+    }
+
+    fn run(mut self) -> Result<(), SelectorError> {
+        // Open brackets with their positions. The flag is set for the
+        // parentheses of `:global(...)`
+        let mut stack: Vec<(usize, char, bool)> = Vec::new();
+
+        while let Some(ch) = self.peek() {
+            let start = self.pos;
+            let in_attribute = stack.iter().any(|(_, open, _)| *open == '[');
+            let in_global = stack.iter().any(|(_, _, global)| *global);
+
+            match ch {
+                '\\' => {
+                    self.bump();
+                    self.bump();
+                }
+                '"' | '\'' => self.string(ch)?,
+                '{' | '}' => self.interpolation()?,
+                '/' if self.src[start..].starts_with("/*") => self.comment()?,
+                ':' if self.handle_classes && self.src[start..].starts_with(":global(") => {
+                    // `:global(...)` is removed; its content is kept as it is
+                    self.pos += ":global(".len();
+                    stack.push((self.pos - 1, '(', true));
+                }
+                '(' | '[' => {
+                    stack.push((start, ch, false));
+                    self.bump();
+                }
+                ')' | ']' => {
+                    let expected = if ch == ')' { '(' } else { '[' };
+                    match stack.pop() {
+                        Some((_, open, global)) if open == expected => {
+                            if global {
+                                self.pos += 1;
+                                continue;
+                            }
+                        }
+                        Some((open_pos, open, _)) => {
+                            return Err(SelectorError::new(
+                                start..start + 1,
+                                format!("Unbalanced brackets: `{ch}` does not match `{open}`"),
+                            )
+                            .with_help(format!("`{open}` was opened at position {open_pos}")));
+                        }
+                        None => {
+                            return Err(SelectorError::new(
+                                start..start + 1,
+                                format!("Unbalanced brackets: unexpected `{ch}`"),
+                            )
+                            .with_help(format!("remove it or add a matching `{expected}`")));
+                        }
+                    }
+                    self.bump();
+                }
+                '.' if self.handle_classes
+                    && !in_attribute
+                    && !in_global
+                    && !self.after_number() =>
+                {
+                    self.class()?;
+                }
+                _ => {
+                    self.bump();
+                }
+            }
+        }
+
+        match stack.pop() {
+            Some((idx, open, _)) => {
+                let close = if open == '(' { ')' } else { ']' };
+                Err(SelectorError::new(
+                    idx..idx + 1,
+                    format!("Unbalanced brackets: `{open}` is never closed"),
+                )
+                .with_help(format!("add a matching `{close}`")))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Checks whether the current position is preceded by the integral
+    /// part of a number, like the `.` in `12.5%`
+    fn after_number(&self) -> bool {
+        let before = &self.src[..self.pos];
+        let word = match before.rfind(|ch: char| !is_name_char(ch)) {
+            Some(idx) => &before[idx + 1..],
+            None => before,
+        };
+        !word.is_empty() && word.chars().all(|ch| ch.is_ascii_digit())
+    }
+
+    fn string(&mut self, quote: char) -> Result<(), SelectorError> {
+        let start = self.pos;
+        self.bump();
+
+        loop {
+            if let Some('{' | '}') = self.peek() {
+                self.interpolation()?;
+                continue;
+            }
+
+            match self.bump() {
+                Some('\\') => {
+                    self.bump();
+                }
+                Some(ch) if ch == quote => return Ok(()),
+                Some(_) => {}
+                None => {
+                    return Err(SelectorError::new(start..start + 1, "Unterminated string")
+                        .with_help(format!("add a matching `{quote}`")));
+                }
+            }
+        }
+    }
+
+    /// Parses `{expr}`, or one of the escaped braces `{{` and `}}`
+    fn interpolation(&mut self) -> Result<(), SelectorError> {
+        let start = self.pos;
+        let rest = &self.src[start..];
+
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            self.bump();
+            self.pos += 1;
+            return Ok(());
+        }
+        if rest.starts_with('}') {
+            return Err(SelectorError::new(start..start + 1, "Unmatched `}`")
+                .with_help("use `}}` for a literal `}`"));
+        }
+
+        let mut depth = 0usize;
+        let len = rest.find(|ch| {
+            match ch {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => {}
+            }
+            depth == 0
+        });
+        let Some(len) = len else {
+            return Err(SelectorError::new(start..start + 1, "Unterminated `{`")
+                .with_help("add a matching `}` or use `{{` for a literal `{`"));
+        };
+
+        let range = start..start + len + 1;
+        let source = &rest[1..len];
+        let expr = match syn::parse_str::<syn::Expr>(source) {
+            Ok(expr) if !source.trim().is_empty() => expr,
+            _ => {
+                return Err(SelectorError::new(range, "Expected a Rust expression")
+                    .with_help("use `{{` and `}}` for literal braces"))
+            }
+        };
+
+        let tokens = quote::ToTokens::into_token_stream(expr);
+        let tokens = respan(tokens, self.header.span);
+        self.header.parts.push(Part::Expr(tokens));
+        self.pos = range.end;
+        Ok(())
+    }
+
+    fn comment(&mut self) -> Result<(), SelectorError> {
+        let start = self.pos;
+        match self.src[start + 2..].find("*/") {
+            Some(len) => {
+                while self.pos < start + 2 + len + 2 {
+                    self.bump();
+                }
+                Ok(())
+            }
+            None => Err(SelectorError::new(start..start + 2, "Unterminated comment")
+                .with_help("add a matching `*/`")),
+        }
+    }
+
+    /// Parses a class selector, i.e. a `.` followed by a classname
+    fn class(&mut self) -> Result<(), SelectorError> {
+        let dot = self.pos;
+        let start = dot + 1;
+
+        let mut end = start;
+        let mut verbatim = false;
+        let mut chars = self.src[start..].chars();
+        while let Some(ch) = chars.next() {
+            if ch == '\\' {
+                verbatim = true;
+                end += ch.len_utf8();
+                end += chars.next().map_or(0, char::len_utf8);
+            } else if is_name_char(ch) {
+                verbatim |= !ch.is_ascii();
+                end += ch.len_utf8();
+            } else {
+                break;
+            }
+        }
+        let classname = &self.src[start..end];
+
+        let rest = &self.src[end..];
+        if rest.starts_with('{') && !rest.starts_with("{{") {
+            return Err(SelectorError::new(
+                dot..end + 1,
+                "Classnames must not contain expressions",
+            )
+            .with_help("use `:global(.{...})` for dynamic classnames; they are not scoped"));
+        }
+
+        if !classname.contains(|ch: char| ch.is_ascii_alphanumeric()) {
+            return Err(
+                SelectorError::new(dot..start, "Expected a classname after `.`")
+                    .with_help("classnames may only contain letters, digits, `-` and `_`"),
+            );
+        }
+
+        if verbatim {
+            // Classnames containing escapes or non-ascii characters cannot be
+            // struct members, so they cannot be scoped
+            return Err(SelectorError::new(
+                dot..end,
+                "Classnames must not contain escapes or non-ascii characters",
+            )
+            .with_help(format!(
+                "use `:global(.{classname})` to keep it as it is; it is not scoped"
+            )));
+        }
+
+        if classname.starts_with(|ch: char| ch.is_ascii_digit()) {
+            return Err(SelectorError::new(
+                start..start + 1,
+                "Classnames must not start with a digit",
+            )
+            .with_help("classnames must start with a letter or `_`"));
+        }
+
+        self.header.push_char('.');
+        self.header
+            .parts
+            .push(Part::ClassName(classname.to_owned()));
+        self.pos = end;
+        Ok(())
+    }
+}
+
+/// Interpolated expressions are parsed from a string. Their tokens get the
+/// span of the header, so errors point to the header instead of the macro
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(group) = &token {
+                let stream = respan(group.stream(), span);
+                let mut group = proc_macro2::Group::new(group.delimiter(), stream);
+                group.set_span(span);
+                token = TokenTree::Group(group);
+            }
+            token.set_span(span);
+            token
+        })
+        .collect()
+}
@@ -0,0 +1,136 @@
+//! SCSS, compiled into css at expansion time using grass. Rust expressions
+//! can be passed in as Sass variables. Sass only sees a placeholder, which
+//! is replaced by the expression within the compiled css. Such variables can
+//! be used as values, but not within Sass functions or calculations.
+
+use std::{
+    cell::RefCell,
+    io,
+    path::{Path, PathBuf},
+};
+
+use proc_macro2::Span;
+use syn::parse::ParseStream;
+
+use super::{css, rules::RuleList};
+
+/// Parses a body of SCSS given as tokens. Variables like `$primary: {theme.primary};`
+/// are passed in from Rust. Imports are resolved relative to the root of the
+/// crate. Returns the rules, and the files read by Sass (e.g. via `@use`).
+pub fn parse(input: ParseStream, span: Span) -> syn::Result<(RuleList, Vec<String>)> {
+    let source = css::Source::from_tokens(input.parse()?)?;
+    let text = source.text();
+
+    let mut scss = String::new();
+    let mut exprs = Vec::new();
+    let mut pos = 0;
+    for (range, expr) in source.exprs() {
+        if !is_variable_value(&text[..range.start]) {
+            continue;
+        }
+
+        scss.push_str(&text[pos..range.start]);
+        scss.push_str(&placeholder(exprs.len()));
+        exprs.push((placeholder(exprs.len()), expr));
+        pos = range.end;
+    }
+    scss.push_str(&text[pos..]);
+
+    let root = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+    let (css, files) = compile(&scss, &root, span)?;
+    let rules = css::parse_text(&css, span, None, &exprs)?;
+    Ok((rules, files))
+}
+
+/// Parses a SCSS file. The given variables are declared in front of its
+/// content. Returns the rules, and all files read, including the given one.
+pub fn parse_file(
+    file: &Path,
+    variables: &[(syn::Ident, syn::Expr)],
+    span: Span,
+) -> syn::Result<(RuleList, Vec<String>)> {
+    let text = std::fs::read_to_string(file).map_err(|err| {
+        let msg = format!("Cannot read `{}`: {err}", file.display());
+        syn::Error::new(span, msg)
+    })?;
+
+    // Declared on the first line, so line numbers within errors stay the same
+    let mut scss = String::new();
+    let mut exprs = Vec::new();
+    for (idx, (name, value)) in variables.iter().enumerate() {
+        scss.push_str(&format!("${name}: {}; ", placeholder(idx)));
+        exprs.push((placeholder(idx), quote::quote!(#value)));
+    }
+    scss.push_str(&text);
+
+    let dir = file.parent().unwrap_or(Path::new(""));
+    let (css, mut files) = compile(&scss, dir, span)?;
+    files.insert(0, file.display().to_string());
+
+    let rules = css::parse_text(&css, span, None, &exprs)?;
+    Ok((rules, files))
+}
+
+/// Placeholders end with `__`, so none of them is a prefix of another one
+fn placeholder(idx: usize) -> String {
+    format!("__css_in_rs_{idx}__")
+}
+
+/// Checks whether the text ends with the name of a Sass variable and a
+/// colon, i.e. the following value is assigned to a variable
+fn is_variable_value(text: &str) -> bool {
+    let Some(text) = text.trim_end().strip_suffix(':') else {
+        return false;
+    };
+    let name_start = text
+        .trim_end()
+        .rfind(|ch: char| !ch.is_ascii_alphanumeric() && ch != '-' && ch != '_');
+    name_start.is_some_and(|idx| text[idx..].starts_with('$'))
+}
+
+/// Compiles SCSS into css. Imports are resolved relative to `load_path`.
+/// Returns the css, and the files read during compilation.
+fn compile(scss: &str, load_path: &Path, span: Span) -> syn::Result<(String, Vec<String>)> {
+    let fs = TrackingFs::default();
+    let options = grass::Options::default()
+        .fs(&fs)
+        .load_path(load_path)
+        .quiet(true);
+
+    let css = grass::from_string(scss, &options).map_err(|err| {
+        let msg = format!("Cannot compile SCSS\n{err}");
+        syn::Error::new(span, msg)
+    })?;
+
+    let files = fs.files.into_inner();
+    let files = files.iter().map(|file| file.display().to_string());
+    Ok((css, files.collect()))
+}
+
+/// Reads files from disk, and remembers them. The generated code depends on
+/// them, so changes trigger a recompilation.
+#[derive(Debug, Default)]
+struct TrackingFs {
+    files: RefCell<Vec<PathBuf>>,
+}
+
+impl grass::Fs for TrackingFs {
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let content = std::fs::read(path)?;
+        let path = std::fs::canonicalize(path)?;
+        self.files.borrow_mut().push(path);
+        Ok(content)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        std::fs::canonicalize(path)
+    }
+}
@@ -8,15 +8,21 @@ pub struct Signature {
 
 impl Parse for Signature {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let (theme_varname, theme_type) = {
-            let param;
-            syn::parenthesized!(param in input);
-
+        // The parentheses around `theme: MyTheme` are optional
+        let parse_param = |param: ParseStream| -> syn::Result<_> {
             let theme_varname = param.parse::<syn::Ident>()?;
             param.parse::<syn::token::Colon>()?;
             let theme_type = param.parse::<syn::Ident>()?;
 
-            (theme_varname, theme_type)
+            Ok((theme_varname, theme_type))
+        };
+
+        let (theme_varname, theme_type) = if input.peek(syn::token::Paren) {
+            let param;
+            syn::parenthesized!(param in input);
+            parse_param(&param)?
+        } else {
+            parse_param(input)?
         };
 
         input.parse::<syn::token::RArrow>()?;
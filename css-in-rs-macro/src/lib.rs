@@ -56,6 +56,451 @@ mod result;
 /// You can inject this style into the DOM using a `StyleProvider` (see
 /// css-in-rs crate). It will hand you a `MyClasses` instance with uniquely
 /// generated classnames (usually something like `css-17`).
+///
+/// Rules named by an identifier define a css class with that name. They may
+/// be followed by pseudo-classes and pseudo-elements, e.g. `item:hover { ... }`,
+/// `item::before { ... }` or `item:not(:disabled) { ... }`. Within rules,
+/// only known pseudo-classes directly after the colon are taken as such, so
+/// `border: solid {color}` is a declaration. More complex selectors can be
+/// given as string literals, e.g. `"div.item > span" { ... }`.
+/// Classnames in selectors may be kebab-case; `.btn-primary` is available as
+/// member `btn_primary`. Classnames which would be mapped to the same member
+/// (like `.a-b` and `.a_b`) are rejected.
+///
+/// Property names given as identifiers are converted to kebab-case (i.e.
+/// `background_color` becomes `background-color`) and checked against a list
+/// of known css properties. Typos result in a compile error. Vendor prefixed
+/// properties like `_webkit_box_orient` are always accepted. For experimental
+/// or otherwise unknown properties, use a string literal instead:
+/// `"text-box-trim": "both"`.
+///
+/// Values given as string literals are checked for obvious mistakes, for
+/// example `display: "flexx"`, `color: "#ggg"` or `width: "10"` (missing unit).
+/// Keyword enums from `css_in_rs::keywords` must match the property, i.e.
+/// `display: keywords::Position::Absolute` is rejected. This is checked by
+/// type, so it also applies to imported enums and values taken from the
+/// theme; other expressions are not checked. Entries with a property given
+/// as a string literal are not checked at all.
+///
+/// # Css values
+/// Values can also be written as plain css, without quotes. Rust expressions
+/// can be spliced into such values using braces:
+/// ```
+/// # use css_in_rs_macro::make_styles;
+/// #[derive(Clone)]
+/// struct MyTheme {
+///     primary_color: String,
+/// }
+///
+/// impl css_in_rs::Theme for MyTheme {
+///     # fn fast_cmp(&self, other: &MyTheme) -> bool { false }
+/// }
+///
+/// make_styles! {
+///     (theme: MyTheme) -> MyClasses {
+///         button {
+///             display: flex,
+///             justify_content: space-between,
+///             margin: 0 auto,
+///             padding: 4px 8px,
+///             font_family: "Open Sans", Arial, sans-serif,
+///             background: rgba(0, 0, 0, 0.5),
+///             width: calc(100% - 16px),
+///             border: 1px solid {theme.primary_color},
+///         },
+///     }
+/// }
+/// ```
+/// A value is taken as a Rust expression if it is one, unless it looks like
+/// css: numbers with a unit (`5px`), hyphenated identifiers (`space-between`),
+/// ratios (`16 / 9`) and css functions (`rgba(...)`) are css. A single
+/// identifier is css if it is a keyword of the property (`display: flex`,
+/// `color: red`, `width: auto`); otherwise it is a Rust variable or constant,
+/// e.g. `width: w`. Within css values, use braces for Rust expressions, e.g.
+/// `transition: opacity {d}ms`. Values like `gap - border` are rejected if
+/// `gap` is a variable of the prologue, since they would be css otherwise.
+///
+/// Some css cannot be written as Rust tokens. Numbers followed by `e` are
+/// exponents in Rust, so `1em`, `2ex` and colors like `#12e` must be quoted
+/// (`"1em"`) or given as an expression (`{Em(1.0)}`). Whitespace is not
+/// part of the tokens; it is inserted where needed, but `a -b` is read as
+/// `a-b` after an identifier.
+///
+/// Any value can be followed by `!important`, including Rust expressions,
+/// e.g. `color: theme.primary !important`, and optional values (see
+/// [Control flow](#control-flow)):
+/// ```
+/// # use css_in_rs_macro::make_styles;
+/// #[derive(Clone)]
+/// struct MyTheme {
+///     primary: String,
+///     outline: Option<String>,
+/// }
+///
+/// impl css_in_rs::Theme for MyTheme {
+///     # fn fast_cmp(&self, other: &MyTheme) -> bool { false }
+/// }
+///
+/// make_styles! {
+///     (theme: MyTheme) -> MyClasses {
+///         button {
+///             color: theme.primary !important,
+///             outline: theme.outline? !important,
+///         },
+///     }
+/// }
+/// # use css_in_rs::Classes;
+/// # let mut theme = MyTheme { primary: "red".into(), outline: Some("1px solid blue".into()) };
+/// # let mut css = String::new();
+/// # MyClasses::generate(&theme, &mut css, &mut 0);
+/// # assert_eq!(css, ".css-0 {\n  color: red !important;\n  \
+/// #     outline: 1px solid blue !important;\n}\n");
+/// # theme.outline = None;
+/// # let mut css = String::new();
+/// # MyClasses::generate(&theme, &mut css, &mut 0);
+/// # assert_eq!(css, ".css-0 {\n  color: red !important;\n}\n");
+/// ```
+///
+/// Fallbacks for older browsers are given as an array. The declaration is
+/// repeated for each of its values, in order: `display: [-webkit-box, flex]`
+/// becomes `display: -webkit-box; display: flex;`.
+///
+/// # Default units
+/// Plain numbers get the default unit of their property: `px` for lengths
+/// like `margin` or `width`, `ms` for durations like `transition_duration`.
+/// This also applies to Rust expressions which turn out to be numbers, like
+/// values of the theme or references to them. Properties taking plain
+/// numbers, like `opacity`, `z_index`, `flex_grow` or `line_height`, are left
+/// alone:
+/// ```
+/// # use css_in_rs_macro::make_styles;
+/// # use css_in_rs::Classes;
+/// #[derive(Clone)]
+/// struct MyTheme {
+///     spacing: u32,
+/// }
+///
+/// impl css_in_rs::Theme for MyTheme {
+///     # fn fast_cmp(&self, other: &MyTheme) -> bool { false }
+/// }
+///
+/// make_styles! {
+///     (theme: MyTheme) -> MyClasses {
+///         let spacing = &theme.spacing;
+///
+///         button {
+///             margin: 8,
+///             padding: theme.spacing * 2,
+///             width: {8 * 2},
+///             gap: spacing,
+///             transition_duration: 150,
+///             line_height: 1.5,
+///         },
+///     }
+/// }
+/// # let mut css = String::new();
+/// # MyClasses::generate(&MyTheme { spacing: 4 }, &mut css, &mut 0);
+/// # assert_eq!(css, ".css-0 {\n  margin: 8px;\n  padding: 8px;\n  width: 16px;\n  \
+/// #     gap: 4px;\n  transition-duration: 150ms;\n  line-height: 1.5;\n}\n");
+/// ```
+/// Shorthands like `border` take no default unit.
+///
+/// # Global classnames
+/// Classnames within `:global(...)` are not scoped and do not become members
+/// of the generated struct. This is useful to target classes which are not
+/// owned by your style, like `":global(.dark-mode) .panel"`. Alternatively,
+/// all rules within a `"@global" { ... }` block are not scoped:
+/// ```
+/// # use css_in_rs_macro::make_styles;
+/// # use css_in_rs::EmptyTheme;
+/// make_styles! {
+///     (_theme: EmptyTheme) -> MyClasses {
+///         "@global" {
+///             "body.dark-mode" {      // emitted as it is
+///                 background_color: "black",
+///             },
+///         },
+///     }
+/// }
+/// ```
+///
+/// # Keyframes
+/// Names of `@keyframes` rules are scoped just like classnames. The generated
+/// name is available as a member of the generated struct. Within the same style,
+/// `animation` and `animation_name` entries given as css or string literals refer
+/// to the scoped name automatically:
+/// ```
+/// # use css_in_rs_macro::make_styles;
+/// # use css_in_rs::EmptyTheme;
+/// make_styles! {
+///     (_theme: EmptyTheme) -> MyClasses {
+///         item {
+///             animation: "shake 0.25s",   // refers to the keyframes below
+///         },
+///         "@keyframes shake" {            // `MyClasses::shake` contains the scoped name
+///             "0%" { transform: "translate(1px, 1px)", },
+///             "100%" { transform: "translate(-1px, -2px)", },
+///         },
+///     }
+/// }
+/// ```
+///
+/// # Nesting
+/// Rules can be nested within other rules, similar to SCSS. Within a nested
+/// selector, `&` refers to the parent selector. If there is no `&`, the
+/// nested selector is treated as a descendant of its parent. At-rules like
+/// `@media` can be nested as well; their entries apply to the parent selector.
+/// ```
+/// # use css_in_rs_macro::make_styles;
+/// # use css_in_rs::EmptyTheme;
+/// make_styles! {
+///     (_theme: EmptyTheme) -> MyClasses {
+///         button {
+///             color: "red",
+///             "&:hover" {             // .button:hover
+///                 color: "blue",
+///             },
+///             "& > .icon" {           // .button > .icon
+///                 margin: "4px",
+///             },
+///             "@media (max-width: 600px)" {
+///                 padding: "2px",     // applies to .button
+///             },
+///         },
+///     }
+/// }
+/// ```
+///
+/// # Interpolation
+/// Selectors and at-rules given as string literals may contain Rust
+/// expressions in braces, e.g. `"@media (min-width: {theme.breakpoint}px)"`.
+/// Use `{{` and `}}` for literal braces. Classnames are detected at compile
+/// time, so they cannot be interpolated; dynamic classnames are possible
+/// within `:global(...)`, but they are not scoped.
+/// ```
+/// # use css_in_rs_macro::make_styles;
+/// # use css_in_rs::EmptyTheme;
+/// const MOBILE: u32 = 600;
+///
+/// make_styles! {
+///     (_theme: EmptyTheme) -> MyClasses {
+///         "@media (max-width: {MOBILE}px)" {
+///             sidebar {
+///                 display: "none",
+///             },
+///         },
+///         "menu:nth-child({MOBILE / 200}n)" {
+///             color: "red",
+///         },
+///     }
+/// }
+/// ```
+///
+/// # Control flow
+/// Entries and rules may be wrapped in `if`, `match` and `for`, e.g. to
+/// depend on the theme. Blocks contain comma separated entries and rules,
+/// just like the surrounding rule. An entry with a value of the form
+/// `expr?` is omitted if `expr` is `None`. Conditions of blocks mixing
+/// entries and nested rules are evaluated twice, so they should be cheap.
+///
+/// The set of classnames is always the same: All classnames are part of the
+/// generated struct, even if their rules are skipped.
+/// ```
+/// # use css_in_rs_macro::make_styles;
+/// #[derive(Clone)]
+/// struct MyTheme {
+///     dense: bool,
+///     border: Option<String>,
+///     columns: u32,
+/// }
+///
+/// impl css_in_rs::Theme for MyTheme {
+///     # fn fast_cmp(&self, other: &MyTheme) -> bool { false }
+/// }
+///
+/// make_styles! {
+///     (theme: MyTheme) -> MyClasses {
+///         button {
+///             if theme.dense {
+///                 padding: "2px",
+///             } else {
+///                 padding: "8px",
+///                 "&:hover" {
+///                     padding: "10px",
+///                 },
+///             },
+///             border: theme.border?,
+///         },
+///         grid {
+///             match theme.columns {
+///                 1 => { display: "block" },
+///                 n => { display: "grid", grid_template_columns: format!("repeat({n}, 1fr)") },
+///             },
+///         },
+///         header {
+///             for position in ["-webkit-sticky", "sticky"] {
+///                 position: {position},
+///             },
+///         },
+///     }
+/// }
+/// # use css_in_rs::Classes;
+/// # let mut theme = MyTheme { dense: true, border: Some("1px solid red".into()), columns: 1 };
+/// # let mut css = String::new();
+/// # MyClasses::generate(&theme, &mut css, &mut 0);
+/// # assert!(css.starts_with(".css-0 {\n  padding: 2px;\n  border: 1px solid red;\n}\n"));
+/// # theme.border = None;
+/// # css.clear();
+/// # MyClasses::generate(&theme, &mut css, &mut 0);
+/// # assert!(css.starts_with(".css-0 {\n  padding: 2px;\n}\n"));
+/// ```
+///
+/// # Bindings
+/// The body may start with `let` statements. They are evaluated once each
+/// time the css is generated, with the theme in scope, and their bindings
+/// can be used in all values, like other variables:
+/// ```
+/// # use css_in_rs_macro::make_styles;
+/// # use css_in_rs::Classes;
+/// #[derive(Clone)]
+/// struct MyTheme {
+///     shadows: Vec<String>,
+///     spacing: u32,
+/// }
+///
+/// impl css_in_rs::Theme for MyTheme {
+///     # fn fast_cmp(&self, other: &MyTheme) -> bool { false }
+/// }
+///
+/// make_styles! {
+///     (theme: MyTheme) -> MyClasses {
+///         let shadow = &theme.shadows[3];
+///         let gap = theme.spacing * 2;
+///
+///         card {
+///             box_shadow: shadow,
+///             padding: gap,
+///             "&:hover" {
+///                 box_shadow: 0 0 8px {shadow},
+///             },
+///         },
+///     }
+/// }
+/// # let shadows = ["none", "none", "none", "red"].map(String::from).to_vec();
+/// # let theme = MyTheme { shadows, spacing: 4 };
+/// # let mut css = String::new();
+/// # MyClasses::generate(&theme, &mut css, &mut 0);
+/// # assert_eq!(css, ".css-0 {\n  box-shadow: red;\n  padding: 8px;\n}\n\
+/// #     .css-0:hover {\n  box-shadow: 0 0 8px red;\n}\n");
+/// ```
+/// The generated code uses the variables `css`, `counter` and `start`, so
+/// these names are not available.
+///
+/// # Other at-rules
+/// At-rules like `@font-face`, `@page`, `@counter-style` or `@property`
+/// contain descriptors instead of rules. Descriptors are checked like
+/// properties. Statements like `@import`, `@charset` or `@layer a, b` have
+/// no block at all. They are only allowed at the top level; `@layer a, b`
+/// may also be used within at-rules like `@media`:
+/// ```
+/// # use css_in_rs_macro::make_styles;
+/// # use css_in_rs::EmptyTheme;
+/// make_styles! {
+///     (_theme: EmptyTheme) -> MyClasses {
+///         "@layer base, components",
+///         "@font-face" {
+///             font_family: "\"Open Sans\"",
+///             src: "url(/fonts/OpenSans.woff2) format(\"woff2\")",
+///         },
+///         "@property --angle" {
+///             syntax: "'<angle>'",
+///             inherits: "false",
+///             initial_value: "0deg",
+///         },
+///         text {
+///             font_family: "\"Open Sans\"",
+///         },
+///     }
+/// }
+/// ```
+///
+/// # Plain css
+/// With `css` in front of the body, it contains plain css instead. It ends
+/// up as the same rules and entries, with the same checks. Values may
+/// contain Rust expressions in braces, just like unquoted values:
+/// ```
+/// # use css_in_rs_macro::make_styles;
+/// # use css_in_rs::{Classes, EmptyTheme};
+/// make_styles! {
+///     (_theme: EmptyTheme) -> MyClasses css {
+///         .button {
+///             background-color: #fff;
+///             padding: 4px {2 * 4}px;
+///             &:hover .icon { color: red; }
+///         }
+///         @media (max-width: 600px) {
+///             .button { display: none; }
+///         }
+///     }
+/// }
+/// # let mut css = String::new();
+/// # MyClasses::generate(&EmptyTheme, &mut css, &mut 0);
+/// # assert_eq!(css, ".css-0 {\n  background-color: #fff;\n  padding: 4px 8px;\n}\n\
+/// #     .css-0:hover .css-1 {\n  color: red;\n}\n\
+/// #     @media (max-width: 600px) {\n.css-0 {\n  display: none;\n}\n}\n");
+/// ```
+/// Whitespace is recovered from the positions of the tokens, since it is
+/// significant in selectors. These are only available with Rust 1.88 or
+/// later; with older compilers, plain css is rejected. The css must still
+/// consist of valid Rust tokens: Use double quotes for strings, and write
+/// values like `1em` or `#12e` as Rust expressions, e.g. `{"1em"}`.
+/// Selectors and at-rules cannot contain Rust expressions, and control flow
+/// is not available. Properties are checked like identifiers in the usual
+/// syntax; unknown ones can be quoted to skip the check, e.g.
+/// `"text-box-trim": both;`.
+///
+/// # SCSS
+/// With the `scss` feature, `scss` in front of the body compiles it as SCSS
+/// at expansion time, using the same syntax as plain css. Imports (e.g. `@use`)
+/// are resolved relative to the root of the crate. Sass variables can be set
+/// to Rust expressions in braces, e.g. to values of the theme. Sass only sees
+/// a placeholder, so these variables can be used as values, but not within
+/// Sass functions or calculations:
+#[cfg_attr(feature = "scss", doc = "```")]
+#[cfg_attr(not(feature = "scss"), doc = "```ignore")]
+/// # use css_in_rs_macro::make_styles;
+/// # use css_in_rs::Classes;
+/// #[derive(Clone)]
+/// struct MyTheme {
+///     primary: String,
+/// }
+///
+/// impl css_in_rs::Theme for MyTheme {
+///     # fn fast_cmp(&self, other: &MyTheme) -> bool { false }
+/// }
+///
+/// make_styles! {
+///     (theme: MyTheme) -> MyClasses scss {
+///         $primary: {theme.primary};
+///         $gap: 4px;
+///
+///         @mixin padded {
+///             padding: $gap * 2;
+///         }
+///
+///         .button {
+///             @include padded;
+///             color: $primary;
+///             &:hover .icon { border: 1px solid $primary; }
+///         }
+///     }
+/// }
+/// # let theme = MyTheme { primary: "red".to_owned() };
+/// # let mut css = String::new();
+/// # MyClasses::generate(&theme, &mut css, &mut 0);
+/// # assert_eq!(css, ".css-0 {\n  padding: 8px;\n  color: red;\n}\n\
+/// #     .css-0:hover .css-1 {\n  border: 1px solid red;\n}\n");
+/// ```
 #[proc_macro]
 pub fn make_styles(input: TokenStream) -> TokenStream {
     let style = syn::parse_macro_input!(input as data::Style);
@@ -67,12 +512,91 @@ pub fn make_styles(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+/// Like [make_styles!], but reads plain css from a file, e.g. a CSS Modules
+/// file. The path is relative to the root of the crate (i.e. the directory
+/// containing `Cargo.toml`). Classnames and keyframes are scoped just like
+/// in [make_styles!], and changes of the file trigger a recompilation.
+/// Errors contain their position within the file.
+/// ```
+/// # use css_in_rs_macro::make_styles_from_file;
+/// # use css_in_rs::{Classes, EmptyTheme};
+/// // .base { padding: 4px; }
+/// // .button { composes: base; color: red; }
+/// // .primary { composes: button; composes: active from global; }
+/// make_styles_from_file!("doc/button.module.css", theme: EmptyTheme -> ButtonClasses);
+///
+/// let classes = ButtonClasses::new(0);
+/// assert_eq!(classes.button, "css-1 css-0");
+/// assert_eq!(classes.primary, "css-2 css-1 css-0 active");
+/// ```
+/// Like in CSS Modules, `composes: a b` adds the classes `a` and `b` to the
+/// class of the rule, so the member contains their scoped names as well.
+/// Classes which are not part of the style can be added using
+/// `composes: a from global`. `composes` is only allowed in rules with a
+/// single class selector. It is available in [make_styles!] as well.
+///
+/// With the `scss` feature, files ending with `.scss` are compiled as SCSS
+/// (see [make_styles!]). Imports are resolved relative to the file. Sass
+/// variables can be set to Rust expressions after the signature:
+#[cfg_attr(feature = "scss", doc = "```")]
+#[cfg_attr(not(feature = "scss"), doc = "```ignore")]
+/// # use css_in_rs_macro::make_styles_from_file;
+/// # use css_in_rs::Classes;
+/// #[derive(Clone)]
+/// struct MyTheme {
+///     primary: String,
+/// }
+///
+/// impl css_in_rs::Theme for MyTheme {
+///     # fn fast_cmp(&self, other: &MyTheme) -> bool { false }
+/// }
+///
+/// // @use "sizes";
+/// // .card { padding: sizes.$gap; border-color: $primary; }
+/// make_styles_from_file!("doc/card.scss", theme: MyTheme -> CardClasses, $primary: theme.primary);
+/// # let theme = MyTheme { primary: "red".to_owned() };
+/// # let mut css = String::new();
+/// # CardClasses::generate(&theme, &mut css, &mut 0);
+/// # assert_eq!(css, ".css-0 {\n  padding: 4px;\n  border-color: red;\n}\n");
+/// ```
+#[proc_macro]
+pub fn make_styles_from_file(input: TokenStream) -> TokenStream {
+    let style = syn::parse_macro_input!(input with data::Style::parse_from_file);
+
+    let result = result::Result::new(style);
+    TokenStream::from(result.to_token_stream())
+}
+
+/// Generates the enums of `css_in_rs::keywords`. Not meant to be used directly
+#[doc(hidden)]
+#[proc_macro]
+pub fn __keyword_enums(_input: TokenStream) -> TokenStream {
+    TokenStream::from(data::rules::entry::keywords::enums())
+}
+
 #[cfg(test)]
 mod tests {
+    use proc_macro2::TokenStream;
     use quote::{quote, ToTokens};
 
     use crate::{data::Style, result};
 
+    /// A value given as Rust expression, with the type-level check of
+    /// keyword enums for a property which does not only take keywords
+    fn checked_value(value: TokenStream, with_unit: TokenStream) -> TokenStream {
+        quote! {
+            match &(#value) {
+                value => {
+                    #[allow(unused_imports)]
+                    use ::css_in_rs::keywords::check::{Keyword as _, Other as _};
+                    (&::css_in_rs::keywords::check::Value(value))
+                        .check::<::css_in_rs::keywords::property::other>();
+                    #with_unit
+                }
+            }
+        }
+    }
+
     #[test]
     fn simple() {
         let input = quote! {
@@ -140,4 +664,353 @@ mod tests {
 
         assert_eq!(result, expected.to_string());
     }
+
+    #[test]
+    fn keyframes() {
+        let input = quote! {
+            (theme: MyTheme) -> MyClasses {
+                item {
+                    animation: "shake 1s, other 2s",
+                },
+                "@keyframes shake" {
+                    "0%" { opacity: "0", },
+                },
+            }
+        };
+
+        let style = syn::parse2::<Style>(input).unwrap();
+        let result = result::Result::new(style);
+        let result = result.to_token_stream().to_string();
+
+        let expected = quote! {
+            struct MyClasses {
+                pub item: String,
+                #[allow(dead_code)]
+                pub shake: String,
+            }
+
+            impl ::css_in_rs::Classes for MyClasses {
+                type Theme = MyTheme;
+
+                fn generate(theme: &Self::Theme, css: &mut String, counter: &mut u64) {
+                    use ::core::fmt::Write;
+                    let start = *counter;
+                    let _ = write!(
+                        css,
+                        ".css-{} {{\n  {}: css-{} 1s, other 2s;\n}}\n@keyframes css-{} {{\n0% {{\n  {}: {};\n}}\n}}\n",
+                        start + 0u64, "animation", start + 1u64,
+                        start + 1u64, "opacity", "0"
+                    );
+                    *counter = start + 2u64;
+                }
+                fn new(start: u64) -> Self {
+                    Self {
+                        item: format!("css-{}", start + 0u64),
+                        shake: format!("css-{}", start + 1u64),
+                    }
+                }
+            }
+        };
+
+        assert_eq!(result, expected.to_string());
+    }
+
+    #[test]
+    fn nested() {
+        let input = quote! {
+            (theme: MyTheme) -> MyClasses {
+                button {
+                    color: "red",
+                    "&:hover, &:focus" {
+                        color: "blue",
+                    },
+                    ".icon" {
+                        margin: "4px",
+                    },
+                    "@media (max-width: 600px)" {
+                        padding: "2px",
+                        "& > span" {},
+                    },
+                },
+            }
+        };
+
+        let style = syn::parse2::<Style>(input).unwrap();
+        let result = result::Result::new(style);
+        let result = result.to_token_stream().to_string();
+
+        let expected = quote! {
+            struct MyClasses {
+                pub button: String,
+                pub icon: String,
+            }
+
+            impl ::css_in_rs::Classes for MyClasses {
+                type Theme = MyTheme;
+
+                fn generate(theme: &Self::Theme, css: &mut String, counter: &mut u64) {
+                    use ::core::fmt::Write;
+                    let start = *counter;
+                    let _ = write!(
+                        css,
+                        ".css-{} {{\n  {}: {};\n}}\n.css-{}:hover, .css-{}:focus {{\n  {}: {};\n}}\n.css-{} .css-{} {{\n  {}: {};\n}}\n@media (max-width: 600px) {{\n.css-{} {{\n  {}: {};\n}}\n.css-{} > span {{\n}}\n}}\n",
+                        start + 0u64, "color", "red",
+                        start + 0u64, start + 0u64, "color", "blue",
+                        start + 0u64, start + 1u64, "margin", "4px",
+                        start + 0u64, "padding", "2px",
+                        start + 0u64
+                    );
+                    *counter = start + 2u64;
+                }
+                fn new(start: u64) -> Self {
+                    Self {
+                        button: format!("css-{}", start + 0u64),
+                        icon: format!("css-{}", start + 1u64),
+                    }
+                }
+            }
+        };
+
+        assert_eq!(result, expected.to_string());
+    }
+
+    #[test]
+    fn control_flow() {
+        let input = quote! {
+            (theme: MyTheme) -> MyClasses {
+                button {
+                    color: "red",
+                    if theme.dense {
+                        padding: "2px",
+                    } else {
+                        padding: "8px",
+                    },
+                    border: theme.border?,
+                },
+            }
+        };
+
+        let style = syn::parse2::<Style>(input).unwrap();
+        let result = result::Result::new(style);
+        let result = result.to_token_stream().to_string();
+
+        let border = checked_value(quote!(value), quote!(value));
+        let expected = quote! {
+            struct MyClasses {
+                pub button: String,
+            }
+
+            impl ::css_in_rs::Classes for MyClasses {
+                type Theme = MyTheme;
+
+                fn generate(theme: &Self::Theme, css: &mut String, counter: &mut u64) {
+                    use ::core::fmt::Write;
+                    let start = *counter;
+                    let _ = write!(css, ".css-{} {{\n  {}: {};\n", start + 0u64, "color", "red");
+                    if theme.dense {
+                        let _ = write!(css, "  {}: {};\n", "padding", "2px");
+                    } else {
+                        let _ = write!(css, "  {}: {};\n", "padding", "8px");
+                    }
+                    if let Some(value) = &(theme.border) {
+                        let _ = write!(css, "  {}: {};\n", "border", #border);
+                    }
+                    let _ = write!(css, "}}\n");
+                    *counter = start + 1u64;
+                }
+                fn new(start: u64) -> Self {
+                    Self {
+                        button: format!("css-{}", start + 0u64),
+                    }
+                }
+            }
+        };
+
+        assert_eq!(result, expected.to_string());
+    }
+
+    #[test]
+    fn important() {
+        let input = quote! {
+            (theme: MyTheme) -> MyClasses {
+                button {
+                    color: theme.primary !important,
+                    display: flex !important,
+                    border: theme.border? !important,
+                },
+            }
+        };
+
+        let style = syn::parse2::<Style>(input).unwrap();
+        let result = result::Result::new(style);
+        let result = result.to_token_stream().to_string();
+
+        let color = checked_value(quote!(theme.primary), quote!(value));
+        let border = checked_value(quote!(value), quote!(value));
+        let expected = quote! {
+            struct MyClasses {
+                pub button: String,
+            }
+
+            impl ::css_in_rs::Classes for MyClasses {
+                type Theme = MyTheme;
+
+                fn generate(theme: &Self::Theme, css: &mut String, counter: &mut u64) {
+                    use ::core::fmt::Write;
+                    let start = *counter;
+                    let _ = write!(
+                        css,
+                        ".css-{} {{\n  {}: {} !important;\n  {}: {} !important;\n",
+                        start + 0u64,
+                        "color",
+                        #color,
+                        "display",
+                        "flex"
+                    );
+                    if let Some(value) = &(theme.border) {
+                        let _ = write!(css, "  {}: {} !important;\n", "border", #border);
+                    }
+                    let _ = write!(css, "}}\n");
+                    *counter = start + 1u64;
+                }
+                fn new(start: u64) -> Self {
+                    Self {
+                        button: format!("css-{}", start + 0u64),
+                    }
+                }
+            }
+        };
+
+        assert_eq!(result, expected.to_string());
+    }
+
+    #[test]
+    fn fallbacks() {
+        let input = quote! {
+            (theme: MyTheme) -> MyClasses {
+                button {
+                    display: [-webkit-box, "flex"],
+                    height: [100vh, theme.height] !important,
+                },
+            }
+        };
+
+        let style = syn::parse2::<Style>(input).unwrap();
+        let result = result::Result::new(style);
+        let result = result.to_token_stream().to_string();
+
+        let height = checked_value(
+            quote!(theme.height),
+            quote! {
+                {
+                    #[allow(unused_imports)]
+                    use ::css_in_rs::values::default_unit::{Number as _, Other as _};
+                    (&::css_in_rs::values::default_unit::Value(value, "px")).with_default_unit()
+                }
+            },
+        );
+        let expected = quote! {
+            struct MyClasses {
+                pub button: String,
+            }
+
+            impl ::css_in_rs::Classes for MyClasses {
+                type Theme = MyTheme;
+
+                fn generate(theme: &Self::Theme, css: &mut String, counter: &mut u64) {
+                    use ::core::fmt::Write;
+                    let start = *counter;
+                    let _ = write!(
+                        css,
+                        ".css-{} {{\n  {}: {};\n  {}: {};\n  {}: {} !important;\n  {}: {} !important;\n}}\n",
+                        start + 0u64,
+                        "display",
+                        "-webkit-box",
+                        "display",
+                        "flex",
+                        "height",
+                        "100vh",
+                        "height",
+                        #height
+                    );
+                    *counter = start + 1u64;
+                }
+                fn new(start: u64) -> Self {
+                    Self {
+                        button: format!("css-{}", start + 0u64),
+                    }
+                }
+            }
+        };
+
+        assert_eq!(result, expected.to_string());
+    }
+
+    #[test]
+    fn prologue() {
+        let input = quote! {
+            (theme: MyTheme) -> MyClasses {
+                let shadow = &theme.shadows[3];
+
+                button {
+                    box_shadow: {shadow},
+                },
+            }
+        };
+
+        let style = syn::parse2::<Style>(input).unwrap();
+        let result = result::Result::new(style);
+        let result = result.to_token_stream().to_string();
+
+        let shadow = checked_value(quote!({ shadow }), quote!(value));
+        let expected = quote! {
+            struct MyClasses {
+                pub button: String,
+            }
+
+            impl ::css_in_rs::Classes for MyClasses {
+                type Theme = MyTheme;
+
+                fn generate(theme: &Self::Theme, css: &mut String, counter: &mut u64) {
+                    use ::core::fmt::Write;
+                    let start = *counter;
+                    let shadow = &theme.shadows[3];
+                    let _ = write!(
+                        css,
+                        ".css-{} {{\n  {}: {};\n}}\n",
+                        start + 0u64,
+                        "box-shadow",
+                        #shadow
+                    );
+                    *counter = start + 1u64;
+                }
+                fn new(start: u64) -> Self {
+                    Self {
+                        button: format!("css-{}", start + 0u64),
+                    }
+                }
+            }
+        };
+
+        assert_eq!(result, expected.to_string());
+
+        let input = quote! {
+            (theme: MyTheme) -> MyClasses {
+                let (start, end) = theme.range;
+                button {},
+            }
+        };
+        let error = syn::parse2::<Style>(input).err().unwrap().to_string();
+        assert!(error.starts_with("`start` is used by the generated code"));
+
+        // Only bindings are checked, not paths and types within the pattern
+        let input = quote! {
+            (theme: MyTheme) -> MyClasses {
+                let shadow: css::Shadow = theme.shadow;
+                let start::Range { end, .. } = theme.range;
+                button {},
+            }
+        };
+        assert!(syn::parse2::<Style>(input).is_ok());
+    }
 }
@@ -1,16 +1,21 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 
+use crate::data::rules::header::field_name;
+
 pub struct Output {
     pub format_str: String,
     pub params: TokenStream,
+    /// Statements preceding the current `write!` command, i.e. control flow
+    statements: TokenStream,
     map: BTreeMap<String, u64>,
+    keyframes: BTreeSet<String>,
 }
 
 impl Output {
-    pub fn new(classnames: &[syn::Ident]) -> Self {
+    pub fn new(classnames: &[syn::Ident], keyframes: BTreeSet<String>) -> Self {
         let mut map = BTreeMap::default();
         for ident in classnames {
             let idx = map.len() as u64;
@@ -21,10 +26,37 @@ impl Output {
         Self {
             format_str: Default::default(),
             params: Default::default(),
+            statements: Default::default(),
             map,
+            keyframes,
+        }
+    }
+
+    /// Creates an empty output for a nested block, e.g. the body of an `if`
+    pub fn nested(&self) -> Self {
+        Self {
+            format_str: Default::default(),
+            params: Default::default(),
+            statements: Default::default(),
+            map: self.map.clone(),
+            keyframes: self.keyframes.clone(),
         }
     }
 
+    /// Finishes the current `write!` command and appends the given statement
+    pub fn push_statement(&mut self, statement: TokenStream) {
+        if !self.format_str.is_empty() {
+            let format_str = std::mem::take(&mut self.format_str);
+            let params = std::mem::take(&mut self.params);
+            quote!(let _ = write!(css, #format_str #params);).to_tokens(&mut self.statements);
+        }
+        statement.to_tokens(&mut self.statements);
+    }
+
+    pub fn is_keyframes(&self, name: &str) -> bool {
+        self.keyframes.contains(name)
+    }
+
     pub fn push_str(&mut self, s: &str) {
         let s = s.replace('{', "{{");
         let s = s.replace('}', "}}");
@@ -32,7 +64,7 @@ impl Output {
     }
 
     pub fn push_classname(&mut self, name: &str) {
-        let id = *self.map.get(name).unwrap();
+        let id = *self.map.get(&field_name(name)).unwrap();
 
         self.format_str.push_str("css-{}");
         quote!(, start + #id).to_tokens(&mut self.params);
@@ -44,14 +76,18 @@ impl ToTokens for Output {
         let format_str = &self.format_str;
         let params = &self.params;
 
-        let ts = quote! {
-            let _ = write!(css, #format_str #params);
-        };
-        ts.to_tokens(tokens);
+        self.statements.to_tokens(tokens);
+        if !format_str.is_empty() || self.statements.is_empty() {
+            let ts = quote! {
+                let _ = write!(css, #format_str #params);
+            };
+            ts.to_tokens(tokens);
+        }
     }
 }
 
 /// Essentially, the `make_styles` creates one huge `write!` command.
+/// Only control flow (e.g. `if` or `for`) splits it into several ones.
 /// It consists of one format string and many params. Whenever we want
 /// to add something to this output, the need to add it to both the
 /// format string and to the param list.
@@ -1,13 +1,20 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use proc_macro2::TokenStream;
 use quote::{quote, quote_spanned, ToTokens, TokenStreamExt};
 
 use crate::{
-    data::{signature::Signature, Style},
+    data::{
+        rules::{entry::Composed, header::field_name},
+        signature::Signature,
+        Style,
+    },
     output::{Output, ToOutput},
 };
 
 struct ClassDefinition<'a> {
     classnames: &'a [syn::Ident],
+    keyframes: &'a BTreeSet<String>,
     classname: &'a syn::Ident,
 }
 
@@ -16,7 +23,14 @@ impl<'a> ToTokens for ClassDefinition<'a> {
         let mut decls = TokenStream::default();
 
         decls.append_all(self.classnames.iter().map(|ident| {
+            // Keyframes are usually only referenced from within the style
+            let is_keyframes = self.keyframes.iter().any(|name| *ident == field_name(name));
+            let attr = match is_keyframes {
+                true => quote!(#[allow(dead_code)]),
+                false => TokenStream::default(),
+            };
             quote_spanned!(ident.span() =>
+                #attr
                 pub #ident: String,
             )
         }));
@@ -36,6 +50,8 @@ impl<'a> ToTokens for ClassDefinition<'a> {
 struct TraitImpl<'a> {
     signature: &'a Signature,
     classnames: &'a [syn::Ident],
+    compositions: &'a BTreeMap<String, Vec<Composed>>,
+    prologue: &'a [syn::Local],
     output: &'a Output,
 }
 
@@ -45,6 +61,7 @@ impl<'a> ToTokens for TraitImpl<'a> {
         let theme_var = &self.signature.theme_varname;
         let theme_type = &self.signature.theme_type;
         let output = self.output;
+        let prologue = self.prologue;
 
         let number_of_classes = self.classnames.len() as u64;
         let setup_classnames =
@@ -53,8 +70,27 @@ impl<'a> ToTokens for TraitImpl<'a> {
                 .enumerate()
                 .map(|(idx, ident)| -> TokenStream {
                     let idx = idx as u64;
+                    let mut format_str = "css-{}".to_owned();
+                    let mut args = vec![quote!(start + #idx)];
+
+                    // Classes composed via `composes: ...` are part of the classname
+                    let composed = self.compositions.get(&ident.to_string());
+                    for composed in composed.into_iter().flatten() {
+                        if composed.global {
+                            format_str.push(' ');
+                            format_str.push_str(&composed.name);
+                            continue;
+                        }
+
+                        let field = field_name(&composed.name);
+                        let idx = self.classnames.iter().position(|ident| *ident == field);
+                        let idx = idx.expect("composed classes are checked while parsing") as u64;
+                        format_str.push_str(" css-{}");
+                        args.push(quote!(start + #idx));
+                    }
+
                     quote! {
-                        #ident: format!("css-{}", start + #idx),
+                        #ident: format!(#format_str, #(#args),*),
                     }
                 });
 
@@ -65,6 +101,7 @@ impl<'a> ToTokens for TraitImpl<'a> {
                 fn generate(#theme_var: &Self::Theme, css: &mut String, counter: &mut u64) {
                     use ::core::fmt::Write;
                     let start = *counter;
+                    #(#prologue)*
                     #output
                     *counter = start + #number_of_classes;
                 }
@@ -83,19 +120,25 @@ impl<'a> ToTokens for TraitImpl<'a> {
 pub struct Result {
     style: Style,
     classnames: Vec<syn::Ident>,
+    keyframes: BTreeSet<String>,
+    compositions: BTreeMap<String, Vec<Composed>>,
     output: Output,
 }
 
 impl Result {
     pub fn new(style: Style) -> Self {
         let classnames = style.get_classnames();
-        let mut output = Output::new(&classnames);
+        let keyframes = style.get_keyframes();
+        let compositions = style.get_compositions();
+        let mut output = Output::new(&classnames, keyframes.clone());
 
         style.rules.append(&mut output);
 
         Self {
             style,
             classnames,
+            keyframes,
+            compositions,
             output,
         }
     }
@@ -107,18 +150,25 @@ impl ToTokens for Result {
         let classnames = &self.classnames;
         let class_def = ClassDefinition {
             classnames,
+            keyframes: &self.keyframes,
             classname: &signature.classname,
         };
 
         let trait_impl = TraitImpl {
             signature,
             classnames,
+            compositions: &self.compositions,
+            prologue: &self.style.prologue,
             output: &self.output,
         };
 
+        // Including the files makes the compiler track them for changes
+        let files = &self.style.files;
+
         let ts = quote! {
             #class_def
             #trait_impl
+            #(const _: &[u8] = include_bytes!(#files);)*
         };
         ts.to_tokens(tokens);
     }
@@ -5,17 +5,156 @@ use crate::Theme;
 
 pub type CssGeneratorFn<T> = fn(&T, &mut String, &mut u64) -> ();
 
+/// A stylesheet which keeps ordering-sensitive statements in front of all
+/// other rules. Browsers ignore `@charset`, `@import` and `@namespace` unless
+/// they come first, so these statements are hoisted to the top and
+/// deduplicated, no matter which style they come from. Statements like
+/// `@layer a, b` are hoisted as well, since they must precede `@import` to
+/// declare the order of layers. Only statements at the top level are
+/// hoisted, i.e. not within blocks like `@media`. Only the first `@charset`
+/// is kept.
+///
+/// # Example
+/// ```
+/// # use css_in_rs::backend::Stylesheet;
+/// let mut sheet = Stylesheet::default();
+/// sheet.push_css(".a {\n  color: red;\n}\n");
+/// sheet.push_css("@import url(\"x.css\");\n.b {\n  color: blue;\n}\n");
+/// sheet.push_css("@layer base, components;\n@import url(\"x.css\");\n");
+/// sheet.push_css("@media print {\n@layer print;\n}\n");
+/// sheet.push_css(".c {\n@import url(\"y.css\");\n}\n");
+///
+/// assert_eq!(
+///     sheet.to_string(),
+///     "@layer base, components;\n@import url(\"x.css\");\n.a {\n  color: red;\n}\n\
+///     .b {\n  color: blue;\n}\n@media print {\n@layer print;\n}\n\
+///     .c {\n@import url(\"y.css\");\n}\n"
+/// );
+/// ```
+#[derive(Default)]
+pub struct Stylesheet {
+    charset: Option<String>,
+    layers: Vec<String>,
+    imports: Vec<String>,
+    namespaces: Vec<String>,
+    rules: String,
+}
+
+impl Stylesheet {
+    /// Adds the given css. Strings and comments are skipped while looking
+    /// for statements, so they may contain braces and semicolons.
+    pub fn push_css(&mut self, css: &str) {
+        let bytes = css.as_bytes();
+        let mut depth = 0usize;
+        // The start of the current rule or statement at the top level
+        let mut start = 0;
+        let mut pos = 0;
+
+        while pos < bytes.len() {
+            match bytes[pos] {
+                quote @ (b'"' | b'\'') => {
+                    pos += 1;
+                    while pos < bytes.len() && bytes[pos] != quote {
+                        pos += if bytes[pos] == b'\\' { 2 } else { 1 };
+                    }
+                }
+                b'/' if bytes.get(pos + 1) == Some(&b'*') => {
+                    pos = match css[pos + 2..].find("*/") {
+                        Some(len) => pos + 2 + len + 1,
+                        None => bytes.len(),
+                    };
+                }
+                b'{' => depth += 1,
+                b'}' => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        self.rules.push_str(&css[start..=pos]);
+                        start = pos + 1;
+                    }
+                }
+                b';' if depth == 0 => {
+                    let statement = &css[start..=pos];
+                    if self.hoist(statement.trim()) {
+                        // Drop the line break following the statement
+                        if bytes.get(pos + 1) == Some(&b'\n') {
+                            pos += 1;
+                        }
+                    } else {
+                        self.rules.push_str(statement);
+                    }
+                    start = pos + 1;
+                }
+                _ => {}
+            }
+            pos += 1;
+        }
+
+        self.rules.push_str(&css[start..]);
+    }
+
+    /// Keeps the given statement in front of all rules if it is one of the
+    /// ordering-sensitive statements. Returns whether it was kept.
+    fn hoist(&mut self, statement: &str) -> bool {
+        let Some(name) = statement.strip_prefix('@') else {
+            return false;
+        };
+        let name = name
+            .split(|ch: char| !ch.is_ascii_alphanumeric() && ch != '-')
+            .next()
+            .unwrap_or_default();
+
+        let statements = match name {
+            "charset" => {
+                self.charset.get_or_insert_with(|| statement.to_string());
+                return true;
+            }
+            "layer" => &mut self.layers,
+            "import" => &mut self.imports,
+            "namespace" => &mut self.namespaces,
+            _ => return false,
+        };
+        if !statements.iter().any(|other| other == statement) {
+            statements.push(statement.to_string());
+        }
+        true
+    }
+
+    /// Removes all rules and statements
+    pub fn clear(&mut self) {
+        *self = Default::default();
+    }
+}
+
+impl std::fmt::Display for Stylesheet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let statements = self
+            .charset
+            .iter()
+            .chain(&self.layers)
+            .chain(&self.imports)
+            .chain(&self.namespaces);
+        for statement in statements {
+            writeln!(f, "{statement}")?;
+        }
+        f.write_str(&self.rules)
+    }
+}
+
 /// css-in-rs is backend agnostic. The default backend is based on web_sys,
 /// but other backends are possible (i.e. just insert css into a string, for
 /// example for server side rendering).
 pub trait Backend<T: Theme>: 'static {
-    /// Replaces all styles managed by this backend by the given CSS string
+    /// Replaces all styles managed by this backend by the given CSS string.
+    /// Ordering-sensitive statements like `@import` are already hoisted to
+    /// the top.
     fn replace_all(&mut self, css: String);
 
     /// Runs a given css generator and add the generated styles. The `generator`
     /// function is expected to append new rules to the given `String`. It may
     /// be empty, in which case the new style is to be returned. Alternatively,
     /// the backend may choose to put in all existing rules, in which case the
-    /// new rules are to be appended.
+    /// new rules are to be appended. Either way, the backend is responsible
+    /// to keep ordering-sensitive statements like `@import` in front of all
+    /// other rules; see [Stylesheet].
     fn run_css_generator(&mut self, generator: CssGeneratorFn<T>, theme: &T, counter: &mut u64);
 }
@@ -2,10 +2,10 @@ use wasm_bindgen::JsCast;
 
 use crate::Theme;
 
-use super::{Backend, CssGeneratorFn};
+use super::{Backend, CssGeneratorFn, Stylesheet};
 
 pub struct WebSysBackend {
-    current_style: String,
+    current_style: Stylesheet,
     styles: web_sys::Element,
 }
 
@@ -34,13 +34,18 @@ impl WebSysBackend {
 
 impl<T: Theme> Backend<T> for WebSysBackend {
     fn replace_all(&mut self, css: String) {
-        self.current_style = css;
-        self.styles.set_text_content(Some(&self.current_style));
+        self.current_style.clear();
+        self.current_style.push_css(&css);
+        self.styles
+            .set_text_content(Some(&self.current_style.to_string()));
     }
 
     fn run_css_generator(&mut self, generator: CssGeneratorFn<T>, theme: &T, counter: &mut u64) {
         // TODO: There is probably a much faster way than to append this style this way
-        (generator)(theme, &mut self.current_style, counter);
-        self.styles.set_text_content(Some(&self.current_style));
+        let mut css = String::new();
+        (generator)(theme, &mut css, counter);
+        self.current_style.push_css(&css);
+        self.styles
+            .set_text_content(Some(&self.current_style.to_string()));
     }
 }
@@ -0,0 +1,435 @@
+use core::{fmt, str::FromStr};
+
+/// An sRGB color with alpha channel. Colors can be parsed from css syntax
+/// (hex, `rgb()`, `hsl()` and named colors) and implement [Display](fmt::Display),
+/// so they can be used as values in [make_styles!](crate::make_styles) directly.
+///
+/// Themes can use them to derive shades instead of hardcoding them:
+/// ```
+/// # use css_in_rs::Color;
+/// let primary: Color = "#1976d2".parse().unwrap();
+///
+/// assert_eq!(primary.darken(0.2).to_string(), "#145ea8");
+/// assert_eq!(primary.lighten(0.5).to_string(), "#8cbbe9");
+/// assert_eq!(primary.alpha(0.5).to_string(), "rgba(25, 118, 210, 0.5)");
+/// assert_eq!(Color::WHITE.mix(Color::BLACK, 0.5).to_string(), "#808080");
+/// assert!(primary.contrast_ratio(Color::WHITE) > 4.5);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    /// The alpha channel, from `0.0` (transparent) to `1.0` (opaque)
+    pub a: f32,
+}
+
+/// The error returned if a string is not a valid css color
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseColorError(String);
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid css color `{}`", self.0)
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    pub const TRANSPARENT: Color = Color::rgba(0, 0, 0, 0.0);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b, a: 1.0 }
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: f32) -> Self {
+        Color { r, g, b, a }
+    }
+
+    /// Creates a color from its hue (in degrees), saturation and
+    /// lightness (both from `0.0` to `1.0`)
+    pub fn hsl(h: f32, s: f32, l: f32) -> Self {
+        Color::hsla(h, s, l, 1.0)
+    }
+
+    /// Like [Color::hsl], but with an alpha channel
+    pub fn hsla(h: f32, s: f32, l: f32, a: f32) -> Self {
+        let s = s.clamp(0.0, 1.0);
+        let l = l.clamp(0.0, 1.0);
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let h = h.rem_euclid(360.0) / 60.0;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u8 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let m = l - c / 2.0;
+
+        Color::rgba(channel(r + m), channel(g + m), channel(b + m), a)
+    }
+
+    /// Returns hue (in degrees), saturation and lightness of this color
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let [r, g, b] = [self.r, self.g, self.b].map(|c| c as f32 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+        let d = max - min;
+
+        if d == 0.0 {
+            return (0.0, 0.0, l);
+        }
+
+        let s = d / (1.0 - (2.0 * l - 1.0).abs());
+        let h = if max == r {
+            ((g - b) / d).rem_euclid(6.0)
+        } else if max == g {
+            (b - r) / d + 2.0
+        } else {
+            (r - g) / d + 4.0
+        };
+
+        (h * 60.0, s, l)
+    }
+
+    /// Parses a css color, e.g. `#ff0000`, `rgb(255 0 0 / 50%)`,
+    /// `hsl(0, 100%, 50%)` or `red`
+    /// ```
+    /// # use css_in_rs::Color;
+    /// assert_eq!(Color::parse("#f00"), Ok(Color::rgb(255, 0, 0)));
+    /// assert_eq!(Color::parse("rgb(255 0 0 / 50%)"), Ok(Color::rgba(255, 0, 0, 0.5)));
+    /// assert_eq!(Color::parse("rgba(255, 0, 0, 0.5)"), Ok(Color::rgba(255, 0, 0, 0.5)));
+    /// assert_eq!(Color::parse("hsl(120deg, 100%, 25%)"), Ok(Color::rgb(0, 128, 0)));
+    /// assert_eq!(Color::parse("RebeccaPurple"), Ok(Color::rgb(102, 51, 153)));
+    /// assert!(Color::parse("#ff00zz").is_err());
+    /// ```
+    pub fn parse(s: &str) -> Result<Self, ParseColorError> {
+        let error = || ParseColorError(s.to_owned());
+        let src = s.trim().to_ascii_lowercase();
+
+        if let Some(hex) = src.strip_prefix('#') {
+            return parse_hex(hex).ok_or_else(error);
+        }
+
+        if let Some((name, args)) = src.strip_suffix(')').and_then(|src| src.split_once('(')) {
+            return parse_function(name.trim(), args).ok_or_else(error);
+        }
+
+        if src == "transparent" {
+            return Ok(Color::TRANSPARENT);
+        }
+
+        let idx = NAMED_COLORS
+            .binary_search_by_key(&src.as_str(), |(name, _)| name)
+            .map_err(|_| error())?;
+        let [_, r, g, b] = NAMED_COLORS[idx].1.to_be_bytes();
+        Ok(Color::rgb(r, g, b))
+    }
+
+    /// Mixes this color with white. `amount` ranges from `0.0` (unchanged)
+    /// to `1.0` (white)
+    pub fn lighten(self, amount: f32) -> Self {
+        let white = Color {
+            a: self.a,
+            ..Color::WHITE
+        };
+        self.mix(white, amount)
+    }
+
+    /// Mixes this color with black. `amount` ranges from `0.0` (unchanged)
+    /// to `1.0` (black)
+    pub fn darken(self, amount: f32) -> Self {
+        let black = Color {
+            a: self.a,
+            ..Color::BLACK
+        };
+        self.mix(black, amount)
+    }
+
+    /// Returns this color with the given alpha channel
+    pub fn alpha(self, a: f32) -> Self {
+        Color { a, ..self }
+    }
+
+    /// Mixes this color with `other`. The `weight` of `other` ranges from
+    /// `0.0` (returns `self`) to `1.0` (returns `other`)
+    pub fn mix(self, other: Color, weight: f32) -> Self {
+        let w = weight.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| channel((a as f32 * (1.0 - w) + b as f32 * w) / 255.0);
+
+        Color {
+            r: mix(self.r, other.r),
+            g: mix(self.g, other.g),
+            b: mix(self.b, other.b),
+            a: self.a * (1.0 - w) + other.a * w,
+        }
+    }
+
+    /// The relative luminance as defined by WCAG, from `0.0` (black) to
+    /// `1.0` (white). The alpha channel is ignored.
+    pub fn luminance(self) -> f32 {
+        let linear = |c: u8| {
+            let c = c as f32 / 255.0;
+            match c <= 0.03928 {
+                true => c / 12.92,
+                false => ((c + 0.055) / 1.055).powf(2.4),
+            }
+        };
+
+        0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b)
+    }
+
+    /// The contrast ratio as defined by WCAG, from `1.0` (no contrast)
+    /// to `21.0` (black on white). WCAG recommends at least `4.5` for text.
+    pub fn contrast_ratio(self, other: Color) -> f32 {
+        let a = self.luminance() + 0.05;
+        let b = other.luminance() + 0.05;
+        a.max(b) / a.min(b)
+    }
+}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Color::parse(s)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.a >= 1.0 {
+            true => write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b),
+            false => write!(f, "rgba({}, {}, {}, {})", self.r, self.g, self.b, self.a),
+        }
+    }
+}
+
+/// Converts a channel from `0.0..=1.0` to `0..=255`
+fn channel(c: f32) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    if !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let digit = |idx: usize| u8::from_str_radix(&hex[idx..idx + 1], 16).unwrap() * 17;
+    let byte = |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).unwrap();
+
+    let color = match hex.len() {
+        3 => Color::rgb(digit(0), digit(1), digit(2)),
+        4 => Color::rgba(digit(0), digit(1), digit(2), digit(3) as f32 / 255.0),
+        6 => Color::rgb(byte(0), byte(2), byte(4)),
+        8 => Color::rgba(byte(0), byte(2), byte(4), byte(6) as f32 / 255.0),
+        _ => return None,
+    };
+    Some(color)
+}
+
+/// Parses the arguments of `rgb()`, `rgba()`, `hsl()` and `hsla()`. Both the
+/// legacy syntax (`rgb(255, 0, 0)`) and the modern syntax (`rgb(255 0 0 / 50%)`)
+/// are supported.
+fn parse_function(name: &str, args: &str) -> Option<Color> {
+    let (args, alpha) = match args.split_once('/') {
+        Some((args, alpha)) => (args, Some(alpha)),
+        None => (args, None),
+    };
+    let mut args: Vec<&str> = args
+        .split(|ch: char| ch == ',' || ch.is_whitespace())
+        .filter(|arg| !arg.is_empty())
+        .collect();
+    let alpha = match (alpha, args.len()) {
+        (Some(alpha), 3) => alpha.trim(),
+        (None, 4) => args.pop()?,
+        (None, 3) => "1",
+        _ => return None,
+    };
+    let alpha = number(alpha, 1.0)?;
+
+    match name {
+        "rgb" | "rgba" => {
+            let c = |arg: &str| number(arg, 255.0).map(|c| channel(c / 255.0));
+            Some(Color::rgba(c(args[0])?, c(args[1])?, c(args[2])?, alpha))
+        }
+        "hsl" | "hsla" => {
+            let h = args[0]
+                .strip_suffix("deg")
+                .unwrap_or(args[0])
+                .parse()
+                .ok()?;
+            let s = args[1].strip_suffix('%')?.parse::<f32>().ok()? / 100.0;
+            let l = args[2].strip_suffix('%')?.parse::<f32>().ok()? / 100.0;
+            Some(Color::hsla(h, s, l, alpha))
+        }
+        _ => None,
+    }
+}
+
+/// Parses a number or a percentage, which is relative to `max`
+fn number(s: &str, max: f32) -> Option<f32> {
+    match s.strip_suffix('%') {
+        Some(percent) => Some(percent.parse::<f32>().ok()? / 100.0 * max),
+        None => s.parse().ok(),
+    }
+}
+
+/// Named css colors, sorted by name
+const NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
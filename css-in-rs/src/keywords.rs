@@ -0,0 +1,98 @@
+//! Typed keywords for css properties which only accept a closed set of
+//! keywords, like `display` or `position`. The enums implement
+//! [Display](core::fmt::Display), so they can be used as values in
+//! [make_styles!](crate::make_styles). The macro checks that the enum belongs
+//! to the property:
+//! ```
+//! # use css_in_rs::{make_styles, EmptyTheme};
+//! use css_in_rs::keywords;
+//!
+//! make_styles! {
+//!     (_theme: EmptyTheme) -> MyClasses {
+//!         toolbar {
+//!             display: keywords::Display::Flex,
+//!             flex_direction: keywords::FlexDirection::RowReverse,
+//!             overflow_x: keywords::Overflow::Hidden,  // same keywords as `overflow`
+//!             cursor: keywords::Cursor::Pointer,
+//!         },
+//!     }
+//! }
+//! ```
+//!
+//! Using the wrong enum is a compile time error:
+//! ```compile_fail
+//! # use css_in_rs::{make_styles, EmptyTheme};
+//! use css_in_rs::keywords;
+//!
+//! make_styles! {
+//!     (_theme: EmptyTheme) -> MyClasses {
+//!         toolbar {
+//!             display: keywords::Position::Absolute,
+//!         },
+//!     }
+//! }
+//! ```
+//!
+//! This is checked by the types of the values, so it also applies to enums
+//! which are imported, aliased or taken from the theme:
+//! ```compile_fail
+//! # use css_in_rs::{make_styles, EmptyTheme};
+//! use css_in_rs::keywords::Position;
+//!
+//! make_styles! {
+//!     (_theme: EmptyTheme) -> MyClasses {
+//!         toolbar {
+//!             display: Position::Absolute,
+//!         },
+//!     }
+//! }
+//! ```
+//!
+//! Plain strings are still accepted, e.g. for combined keywords like
+//! `display: "inline flex"`.
+
+/// Implemented by all keyword enums
+pub trait Keyword: Copy + core::fmt::Display {
+    /// The css property the enum is meant for, e.g. `display`
+    const PROPERTY: &'static str;
+}
+
+/// Implemented by keyword enums for each property accepting their keywords.
+/// `P` is a marker type of the property.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a keyword of this css property",
+    label = "expected a keyword of the css property `{P}`"
+)]
+pub trait KeywordOf<P>: Keyword {}
+
+/// The type-level check of values used by [make_styles!](crate::make_styles).
+/// Keyword enums get [Keyword::check], which requires the enum to match the
+/// property. All other values get [Other::check], which accepts anything.
+#[doc(hidden)]
+pub mod check {
+    use super::KeywordOf;
+
+    pub struct Value<'a, T: ?Sized>(pub &'a T);
+
+    pub trait Keyword {
+        type Enum;
+
+        fn check<P>(&self)
+        where
+            Self::Enum: KeywordOf<P>,
+        {
+        }
+    }
+
+    impl<K: super::Keyword> Keyword for Value<'_, K> {
+        type Enum = K;
+    }
+
+    pub trait Other {
+        fn check<P>(&self) {}
+    }
+
+    impl<T: ?Sized> Other for &Value<'_, T> {}
+}
+
+css_in_rs_macro::__keyword_enums!();
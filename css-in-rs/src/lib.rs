@@ -9,7 +9,8 @@
 //! in the final wasm binary.
 //!
 //! Features:
-//! * A procmacro [make_styles!] to write css directly in Rust
+//! * A procmacro [make_styles!] to write css directly in Rust, or
+//!   [make_styles_from_file!] to load it from a css file (e.g. CSS Modules)
 //! * A runtime to inject the styles on a as-need basis. If styles are not used, they
 //!   won't be included in the final binary
 //! * Styles will only be mounted once, even if requested multiple times
@@ -59,7 +60,7 @@
 //!             border: "2px solid red",
 //!         },
 //!         "button.disabled" { // Shows a rust warning: "field never read"
-//!             disabled: true,
+//!             opacity: "0.5",
 //!         },
 //!     }
 //! }
@@ -99,11 +100,15 @@
 #[doc_cfg(feature = "dioxus")]
 use dioxus::prelude::*;
 
+mod color;
 mod style_provider;
 
 pub mod backend;
+pub mod keywords;
+pub mod values;
 
-pub use css_in_rs_macro::make_styles;
+pub use color::{Color, ParseColorError};
+pub use css_in_rs_macro::{make_styles, make_styles_from_file};
 use doc_cfg::doc_cfg;
 pub use style_provider::StyleProvider;
 
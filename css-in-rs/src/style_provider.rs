@@ -7,7 +7,7 @@ use dioxus::core::ScopeState;
 use doc_cfg::doc_cfg;
 
 use crate::{
-    backend::{Backend, CssGeneratorFn},
+    backend::{Backend, CssGeneratorFn, Stylesheet},
     Classes, Theme,
 };
 
@@ -160,12 +160,15 @@ impl<T: Theme> Inner<T> {
     }
 
     fn update(&mut self) {
+        let mut sheet = Stylesheet::default();
         let mut css = String::default();
         for generator in &self.generators {
+            css.clear();
             generator.generate(&self.current_theme, &mut css);
+            sheet.push_css(&css);
         }
 
-        self.backend.replace_all(css);
+        self.backend.replace_all(sheet.to_string());
     }
 
     pub fn update_theme(&mut self, theme: T) {
@@ -0,0 +1,213 @@
+//! Typed css values. They implement [Display](core::fmt::Display), so they
+//! can be used as values in [make_styles!](crate::make_styles) directly, and
+//! they support arithmetic within the same unit; see [Calc] for mixed units.
+//! This is useful for themes:
+//! ```
+//! # use css_in_rs::{make_styles, Theme};
+//! use css_in_rs::values::{Duration, Px};
+//!
+//! #[derive(Clone)]
+//! struct MyTheme {
+//!     spacing: Px,
+//!     transition: Duration,
+//! }
+//!
+//! impl Theme for MyTheme {
+//!     fn fast_cmp(&self, other: &Self) -> bool {
+//!         self.spacing == other.spacing && self.transition == other.transition
+//!     }
+//! }
+//!
+//! make_styles! {
+//!     (theme: MyTheme) -> MyClasses {
+//!         button {
+//!             padding: theme.spacing * 2.0,   // e.g. `16px`
+//!             margin: -theme.spacing,         // e.g. `-8px`
+//!             transition_duration: theme.transition,
+//!         },
+//!     }
+//! }
+//! ```
+//!
+//! Values are displayed with their unit:
+//! ```
+//! # use css_in_rs::values::{Angle, Duration, Percent, Px};
+//! assert_eq!((Px(8.0) * 2.0 + Px(1.5)).to_string(), "17.5px");
+//! assert_eq!((Percent(100.0) / 3.0).to_string(), "33.333332%");
+//! assert_eq!(Duration::secs(0.2).to_string(), "200ms");
+//! assert_eq!(Angle::turn(0.5).to_string(), "180deg");
+//! ```
+
+use core::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+};
+
+mod calc;
+#[doc(hidden)]
+pub mod default_unit;
+
+pub use calc::Calc;
+
+macro_rules! unit {
+    ($(#[$attr:meta])* $name:ident, $unit:literal) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+        pub struct $name(pub f32);
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}{}", self.0, $unit)
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                $name(self.0 + rhs.0)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                $name(self.0 - rhs.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, rhs: Self) {
+                self.0 += rhs.0;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, rhs: Self) {
+                self.0 -= rhs.0;
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $name(-self.0)
+            }
+        }
+
+        impl Mul<f32> for $name {
+            type Output = Self;
+
+            fn mul(self, rhs: f32) -> Self {
+                $name(self.0 * rhs)
+            }
+        }
+
+        impl Mul<$name> for f32 {
+            type Output = $name;
+
+            fn mul(self, rhs: $name) -> $name {
+                $name(self * rhs.0)
+            }
+        }
+
+        impl Div<f32> for $name {
+            type Output = Self;
+
+            fn div(self, rhs: f32) -> Self {
+                $name(self.0 / rhs)
+            }
+        }
+
+        /// The ratio of two values
+        impl Div for $name {
+            type Output = f32;
+
+            fn div(self, rhs: Self) -> f32 {
+                self.0 / rhs.0
+            }
+        }
+
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::default(), Add::add)
+            }
+        }
+
+        impl From<$name> for Calc {
+            fn from(value: $name) -> Calc {
+                Calc::Value(value.0, $unit)
+            }
+        }
+    };
+}
+
+unit!(
+    /// A length in pixels, e.g. `Px(16.0)` is displayed as `16px`
+    Px,
+    "px"
+);
+unit!(
+    /// A length relative to the font size of the element, e.g. `1.5em`
+    Em,
+    "em"
+);
+unit!(
+    /// A length relative to the font size of the root element, e.g. `2rem`
+    Rem,
+    "rem"
+);
+unit!(
+    /// A percentage, e.g. `Percent(50.0)` is displayed as `50%`
+    Percent,
+    "%"
+);
+unit!(
+    /// A length relative to the width of the viewport, e.g. `100vw`
+    Vw,
+    "vw"
+);
+unit!(
+    /// A length relative to the height of the viewport, e.g. `100vh`
+    Vh,
+    "vh"
+);
+unit!(
+    /// A duration in milliseconds, e.g. `Duration(150.0)` is displayed as `150ms`.
+    /// Use [Duration::secs] for durations given in seconds.
+    Duration,
+    "ms"
+);
+unit!(
+    /// An angle in degrees, e.g. `Angle(90.0)` is displayed as `90deg`.
+    /// Use [Angle::rad] or [Angle::turn] for other units.
+    Angle,
+    "deg"
+);
+
+impl Duration {
+    pub fn millis(ms: f32) -> Self {
+        Duration(ms)
+    }
+
+    pub fn secs(secs: f32) -> Self {
+        Duration(secs * 1000.0)
+    }
+}
+
+impl Angle {
+    pub fn deg(deg: f32) -> Self {
+        Angle(deg)
+    }
+
+    pub fn rad(rad: f32) -> Self {
+        Angle(rad.to_degrees())
+    }
+
+    pub fn turn(turns: f32) -> Self {
+        Angle(turns * 360.0)
+    }
+}
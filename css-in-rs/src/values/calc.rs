@@ -0,0 +1,269 @@
+use core::{
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+/// A math expression combining values of different units, rendered as
+/// `calc()`, `min()`, `max()` or `clamp()`. Operations on values of the same
+/// unit are computed right away, so the resulting css stays simple.
+///
+/// Values are converted using [From], e.g. `Calc::from(Px(16.0))`. Use
+/// [Calc::raw] for anything else, like `var(--gap)`.
+/// ```
+/// # use css_in_rs::values::{Calc, Percent, Px, Rem, Vw};
+/// let gap = Calc::from(Percent(100.0)) - Px(16.0) - Px(8.0);
+/// assert_eq!(gap.to_string(), "calc(100% - 24px)");
+///
+/// let width = (Calc::from(Px(300.0)) + Px(20.0)) * 2.0;
+/// assert_eq!(width.to_string(), "640px");
+///
+/// let size = Calc::clamp(Rem(1.0), Calc::from(Vw(2.5)) + Rem(0.5), Rem(2.0));
+/// assert_eq!(size.to_string(), "clamp(1rem, 2.5vw + 0.5rem, 2rem)");
+///
+/// let padding = Calc::max(Px(16.0), Calc::raw("var(--padding)") / 2.0);
+/// assert_eq!(padding.to_string(), "max(16px, var(--padding) / 2)");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Calc {
+    /// A number with a unit, e.g. `16px`. The unit is empty for plain numbers
+    Value(f32, &'static str),
+    /// Arbitrary css, e.g. `var(--gap)`
+    Raw(String),
+    /// A sum of at least two terms
+    Sum(Vec<Calc>),
+    Product(Box<Calc>, f32),
+    Quotient(Box<Calc>, f32),
+    Min(Vec<Calc>),
+    Max(Vec<Calc>),
+    Clamp(Box<Calc>, Box<Calc>, Box<Calc>),
+}
+
+impl Calc {
+    /// Arbitrary css which is used as it is, e.g. `var(--gap)` or `env(...)`
+    pub fn raw(css: impl Into<String>) -> Self {
+        Calc::Raw(css.into())
+    }
+
+    /// The smaller of both values. Computed right away if both have the same unit
+    pub fn min(a: impl Into<Calc>, b: impl Into<Calc>) -> Self {
+        Calc::extremum(a.into(), b.into(), f32::min, Calc::Min, |calc| match calc {
+            Calc::Min(args) => Ok(args),
+            calc => Err(calc),
+        })
+    }
+
+    /// The larger of both values. Computed right away if both have the same unit
+    pub fn max(a: impl Into<Calc>, b: impl Into<Calc>) -> Self {
+        Calc::extremum(a.into(), b.into(), f32::max, Calc::Max, |calc| match calc {
+            Calc::Max(args) => Ok(args),
+            calc => Err(calc),
+        })
+    }
+
+    /// Restricts `value` to the range from `min` to `max`. Computed right away
+    /// if all of them have the same unit
+    pub fn clamp(min: impl Into<Calc>, value: impl Into<Calc>, max: impl Into<Calc>) -> Self {
+        match (min.into(), value.into(), max.into()) {
+            (Calc::Value(min, u1), Calc::Value(value, u2), Calc::Value(max, u3))
+                if u1 == u2 && u2 == u3 && min <= max =>
+            {
+                Calc::Value(value.clamp(min, max), u1)
+            }
+            (min, value, max) => Calc::Clamp(Box::new(min), Box::new(value), Box::new(max)),
+        }
+    }
+
+    fn extremum(
+        a: Calc,
+        b: Calc,
+        f: fn(f32, f32) -> f32,
+        make: fn(Vec<Calc>) -> Calc,
+        args: fn(Calc) -> Result<Vec<Calc>, Calc>,
+    ) -> Self {
+        let mut result: Vec<Calc> = Vec::new();
+        for arg in [a, b] {
+            let arg = args(arg).unwrap_or_else(|arg| vec![arg]);
+            for arg in arg {
+                let same_unit = result.iter_mut().find_map(|other| match (other, &arg) {
+                    (Calc::Value(x, u1), Calc::Value(y, u2)) if u1 == u2 => Some((x, *y)),
+                    _ => None,
+                });
+                match same_unit {
+                    Some((x, y)) => *x = f(*x, y),
+                    None => result.push(arg),
+                }
+            }
+        }
+
+        match result.len() {
+            1 => result.pop().unwrap(),
+            _ => make(result),
+        }
+    }
+
+    /// Splits a sum into its terms
+    fn into_terms(self) -> Vec<Calc> {
+        match self {
+            Calc::Sum(terms) => terms,
+            calc => vec![calc],
+        }
+    }
+
+    /// Builds a sum, adding up terms with the same unit
+    fn sum(terms: impl IntoIterator<Item = Calc>) -> Self {
+        let mut result: Vec<Calc> = Vec::new();
+        for term in terms {
+            let same_unit = result.iter_mut().find_map(|other| match (other, &term) {
+                (Calc::Value(x, u1), Calc::Value(y, u2)) if u1 == u2 => Some((x, *y)),
+                _ => None,
+            });
+            match same_unit {
+                Some((x, y)) => *x += y,
+                None => result.push(term),
+            }
+        }
+
+        if result.len() > 1 {
+            result.retain(|term| !matches!(term, Calc::Value(x, _) if *x == 0.0));
+        }
+        match result.len() {
+            0 => Calc::Value(0.0, ""),
+            1 => result.pop().unwrap(),
+            _ => Calc::Sum(result),
+        }
+    }
+
+    /// Checks whether this is a negative term, which is rendered as
+    /// subtraction within a sum
+    fn is_negative(&self) -> bool {
+        match self {
+            Calc::Value(x, _) | Calc::Product(_, x) => x.is_sign_negative(),
+            _ => false,
+        }
+    }
+
+    /// Writes this expression as it appears within a math function, i.e.
+    /// without a surrounding `calc()`
+    fn fmt_inner(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Calc::Value(x, unit) => write!(f, "{x}{unit}"),
+            Calc::Raw(css) => f.write_str(css),
+            Calc::Sum(terms) => {
+                for (idx, term) in terms.iter().enumerate() {
+                    match (idx, term.is_negative()) {
+                        (0, _) => term.fmt_inner(f)?,
+                        (_, true) => {
+                            f.write_str(" - ")?;
+                            (-term.clone()).fmt_inner(f)?;
+                        }
+                        (_, false) => {
+                            f.write_str(" + ")?;
+                            term.fmt_inner(f)?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+            Calc::Product(calc, factor) => {
+                calc.fmt_inner(f)?;
+                write!(f, " * {factor}")
+            }
+            Calc::Quotient(calc, divisor) => {
+                calc.fmt_inner(f)?;
+                write!(f, " / {divisor}")
+            }
+            Calc::Min(args) => fmt_function(f, "min", args.iter()),
+            Calc::Max(args) => fmt_function(f, "max", args.iter()),
+            Calc::Clamp(min, value, max) => {
+                fmt_function(f, "clamp", [&**min, &**value, &**max].into_iter())
+            }
+        }
+    }
+}
+
+fn fmt_function<'a>(
+    f: &mut fmt::Formatter<'_>,
+    name: &str,
+    args: impl Iterator<Item = &'a Calc>,
+) -> fmt::Result {
+    write!(f, "{name}(")?;
+    for (idx, arg) in args.enumerate() {
+        if idx > 0 {
+            f.write_str(", ")?;
+        }
+        arg.fmt_inner(f)?;
+    }
+    f.write_str(")")
+}
+
+impl fmt::Display for Calc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Calc::Sum(_) | Calc::Product(..) | Calc::Quotient(..) => {
+                f.write_str("calc(")?;
+                self.fmt_inner(f)?;
+                f.write_str(")")
+            }
+            calc => calc.fmt_inner(f),
+        }
+    }
+}
+
+impl From<f32> for Calc {
+    fn from(value: f32) -> Self {
+        Calc::Value(value, "")
+    }
+}
+
+impl<T: Into<Calc>> Add<T> for Calc {
+    type Output = Calc;
+
+    fn add(self, rhs: T) -> Calc {
+        let terms = self.into_terms().into_iter();
+        Calc::sum(terms.chain(rhs.into().into_terms()))
+    }
+}
+
+impl<T: Into<Calc>> Sub<T> for Calc {
+    type Output = Calc;
+
+    fn sub(self, rhs: T) -> Calc {
+        self + -rhs.into()
+    }
+}
+
+impl Neg for Calc {
+    type Output = Calc;
+
+    fn neg(self) -> Calc {
+        self * -1.0
+    }
+}
+
+impl Mul<f32> for Calc {
+    type Output = Calc;
+
+    fn mul(self, rhs: f32) -> Calc {
+        match self {
+            Calc::Value(x, unit) => Calc::Value(x * rhs, unit),
+            Calc::Sum(terms) => Calc::sum(terms.into_iter().map(|term| term * rhs)),
+            Calc::Product(calc, factor) => match factor * rhs {
+                1.0 => *calc,
+                factor => Calc::Product(calc, factor),
+            },
+            calc => Calc::Product(Box::new(calc), rhs),
+        }
+    }
+}
+
+impl Div<f32> for Calc {
+    type Output = Calc;
+
+    fn div(self, rhs: f32) -> Calc {
+        match self {
+            Calc::Value(x, unit) => Calc::Value(x / rhs, unit),
+            Calc::Sum(terms) => Calc::sum(terms.into_iter().map(|term| term / rhs)),
+            calc => Calc::Quotient(Box::new(calc), rhs),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Support for default units, used by the code generated by
+//! [make_styles!](crate::make_styles). Values of properties like `margin`
+//! are wrapped into [Value]. Plain numbers are displayed with the default
+//! unit of the property, everything else is displayed as it is.
+//!
+//! Method resolution prefers [Number] over [Other], since the latter is only
+//! implemented for references:
+//! ```
+//! use css_in_rs::values::default_unit::{Number as _, Other as _, Value};
+//!
+//! assert_eq!((&Value(&8_u32, "px")).with_default_unit().to_string(), "8px");
+//! assert_eq!((&Value(&1.5_f32, "ms")).with_default_unit().to_string(), "1.5ms");
+//! assert_eq!((&Value(&&4_u32, "px")).with_default_unit().to_string(), "4px");
+//! assert_eq!((&Value(&"auto", "px")).with_default_unit().to_string(), "auto");
+//! ```
+
+use core::fmt;
+
+/// A value, and the default unit of its property
+pub struct Value<'a, T: ?Sized>(pub &'a T, pub &'static str);
+
+/// A number followed by a unit, e.g. `8px`
+pub struct WithUnit<T>(T, &'static str);
+
+impl<T: fmt::Display> fmt::Display for WithUnit<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.0, self.1)
+    }
+}
+
+/// Implemented for plain numbers and references to them, which get the
+/// default unit
+pub trait Number {
+    type Output: fmt::Display;
+
+    fn with_default_unit(&self) -> Self::Output;
+}
+
+/// Implemented for all other values, which are displayed as they are
+pub trait Other<'a> {
+    type Output: fmt::Display + ?Sized;
+
+    fn with_default_unit(&self) -> &'a Self::Output;
+}
+
+macro_rules! number {
+    ($($ty:ty),*) => {
+        $(
+            impl Number for Value<'_, $ty> {
+                type Output = WithUnit<$ty>;
+
+                fn with_default_unit(&self) -> WithUnit<$ty> {
+                    WithUnit(*self.0, self.1)
+                }
+            }
+
+            impl Number for Value<'_, &$ty> {
+                type Output = WithUnit<$ty>;
+
+                fn with_default_unit(&self) -> WithUnit<$ty> {
+                    WithUnit(**self.0, self.1)
+                }
+            }
+        )*
+    };
+}
+
+number!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+impl<'a, T: fmt::Display + ?Sized> Other<'a> for &Value<'a, T> {
+    type Output = T;
+
+    fn with_default_unit(&self) -> &'a T {
+        self.0
+    }
+}
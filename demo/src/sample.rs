@@ -11,6 +11,8 @@ fn main() {
 
 make_styles! {
     (theme: MuiTheme) -> CssClasses {
+        let shadow = &theme.shadows[3];
+
         container {
             display: "flex",
             justify_content: "center",
@@ -18,7 +20,7 @@ make_styles! {
         },
         ".container > .item" {
             background_color: "#e2deff",
-            box_shadow: &theme.shadows[3],
+            box_shadow: shadow,
             cursor: "pointer",
             padding: "16px",
             user_select: "none",